@@ -13,12 +13,12 @@ fn load_curves(filepath: &str) -> Result<Vec<Curves>> {
     let mut result = vec![];
 
     let file = std::fs::File::open(filepath)?;
-    let mut reader = MemMappedReader::new(file)?;
-    // let mut reader = FileReader::new(file)?;
+    let reader = MemMappedReader::new(file)?;
+    // let reader = FileReader::new(file)?;
 
-    let archive = Archive::new(&mut reader)?;
+    let archive = Archive::new(&reader)?;
 
-    let mut stack = vec![archive.load_root_object(&mut reader)?];
+    let mut stack = vec![archive.load_root_object(&reader)?];
     loop {
         if stack.is_empty() {
             break;
@@ -26,10 +26,10 @@ fn load_curves(filepath: &str) -> Result<Vec<Curves>> {
 
         let current = stack.pop().unwrap();
 
-        match Schema::parse(&current, &mut reader, &archive) {
+        match Schema::parse(&current, &reader, &archive) {
             Ok(schema) => {
                 if let Schema::Curves(curves) = schema {
-                    let positions = curves.load_positions_sample(0, &mut reader)?;
+                    let positions = curves.load_positions_sample(0, &reader)?;
 
                     result.push(Curves { positions });
                 }
@@ -42,7 +42,7 @@ fn load_curves(filepath: &str) -> Result<Vec<Curves>> {
         for i in (0..child_count).rev() {
             let child = current.load_child(
                 i,
-                &mut reader,
+                &reader,
                 &archive.indexed_meta_data,
                 &archive.time_samplings,
             )?;
@@ -1,6 +1,6 @@
 use ogawa_rs::*;
 
-fn print_chunk_tree(root_group: &GroupChunk, reader: &mut dyn ArchiveReader) -> Result<()> {
+fn print_chunk_tree(root_group: &GroupChunk, reader: &dyn ArchiveReader) -> Result<()> {
     let mut total_data_size = 0;
     let mut data_count = 0;
     let mut group_count = 0;
@@ -56,7 +56,7 @@ fn print_chunk_tree(root_group: &GroupChunk, reader: &mut dyn ArchiveReader) ->
     Ok(())
 }
 
-fn print_object_structure(reader: &mut dyn ArchiveReader, archive: &Archive) -> Result<()> {
+fn print_object_structure(reader: &dyn ArchiveReader, archive: &Archive) -> Result<()> {
     let object_reader = archive.load_root_object(reader)?;
 
     let mut stack = vec![(0, object_reader)];
@@ -162,16 +162,16 @@ fn main() -> anyhow::Result<()> {
     anyhow::ensure!(args.len() == 2, "Expecting one filename argument.");
 
     let file = std::fs::File::open(&args[1])?;
-    let mut reader = MemMappedReader::new(file)?;
-    // let mut reader = FileReader::new(file)?;
+    let reader = MemMappedReader::new(file)?;
+    // let reader = FileReader::new(file)?;
 
-    let archive = Archive::new(&mut reader)?;
+    let archive = Archive::new(&reader)?;
 
     println!("------ print_chunk_tree ------");
-    print_chunk_tree(&archive.root_group, &mut reader)?;
+    print_chunk_tree(&archive.root_group, &reader)?;
 
     println!("------ print_object_structure ------");
-    print_object_structure(&mut reader, &archive)?;
+    print_object_structure(&reader, &archive)?;
 
     Ok(())
 }
@@ -5,12 +5,12 @@ fn main() -> anyhow::Result<()> {
     anyhow::ensure!(args.len() == 2, "Expecting one filename argument.");
 
     let file = std::fs::File::open(&args[1])?;
-    let mut reader = MemMappedReader::new(file)?;
-    // let mut reader = FileReader::new(file)?;
+    let reader = MemMappedReader::new(file)?;
+    // let reader = FileReader::new(file)?;
 
-    let archive = Archive::new(&mut reader)?;
+    let archive = Archive::new(&reader)?;
 
-    let mut stack = vec![archive.load_root_object(&mut reader)?];
+    let mut stack = vec![archive.load_root_object(&reader)?];
     loop {
         if stack.is_empty() {
             break;
@@ -18,7 +18,7 @@ fn main() -> anyhow::Result<()> {
 
         let current = stack.pop().unwrap();
 
-        match Schema::parse(&current, &mut reader, &archive) {
+        match Schema::parse(&current, &reader, &archive) {
             Ok(schema) => match &schema {
                 Schema::BaseGeom(_) => println!("base geometry schema."),
                 Schema::Curves(curves) => {
@@ -40,15 +40,15 @@ fn main() -> anyhow::Result<()> {
                     println!("\tcurves.has_knots() -> {}", curves.has_knots());
 
                     let (curve_type, curve_periodicity, basis_type) =
-                        curves.load_curve_type_sample(0, &mut reader)?;
+                        curves.load_curve_type_sample(0, &reader)?;
                     println!("\tcurve type: {:?}", curve_type);
                     println!("\tcurve periodicity: {:?}", curve_periodicity);
                     println!("\tbasis type: {:?}", basis_type);
 
-                    let positions = curves.load_positions_sample(0, &mut reader)?;
+                    let positions = curves.load_positions_sample(0, &reader)?;
                     println!("\tnumber of positions: {}", positions.len());
 
-                    let n_vertices = curves.load_n_vertices_sample(0, &mut reader)?;
+                    let n_vertices = curves.load_n_vertices_sample(0, &reader)?;
                     println!("\tnumber of curves: {}", n_vertices.len());
 
                     println!(
@@ -72,8 +72,88 @@ fn main() -> anyhow::Result<()> {
                         polymesh.has_velocities()
                     );
 
-                    let n_vertices = polymesh.load_vertices_sample(0, &mut reader)?;
+                    let n_vertices = polymesh.load_vertices_sample(0, &reader)?;
                     println!("\tnumber of vertices: {}", n_vertices.len());
+                    for name in polymesh.uv_set_names() {
+                        let uv = polymesh.load_uv_set_sample(name, 0, &reader)?;
+                        println!("\tnumber of uvs in {}: {}", name, uv.len());
+                    }
+                    for face_set in polymesh.face_sets() {
+                        println!("\tface set: {}", face_set.name);
+                    }
+                }
+                Schema::Points(points) => {
+                    println!("points schema.");
+                    println!("\tpoints.is_constant() -> {}", points.is_constant());
+                    println!("\tpoints.has_velocities() -> {}", points.has_velocities());
+                    println!("\tpoints.has_widths() -> {}", points.has_widths());
+
+                    let positions = points.load_positions_sample(0, &reader)?;
+                    println!("\tnumber of points: {}", positions.len());
+                }
+                Schema::SubD(subd) => {
+                    println!("subd schema.");
+                    println!("\tsubd.is_constant() -> {}", subd.is_constant());
+                    println!("\tsubd.has_creases() -> {}", subd.has_creases());
+                    println!("\tsubd.has_corners() -> {}", subd.has_corners());
+                    println!("\tsubd.has_holes() -> {}", subd.has_holes());
+                    println!("\tsubd.has_uv() -> {}", subd.has_uv());
+
+                    let scheme = subd.load_subdivision_scheme_sample(0, &reader)?;
+                    println!("\tsubdivision scheme: {}", scheme);
+                }
+                Schema::NuPatch(nupatch) => {
+                    println!("nupatch schema.");
+                    println!("\tnupatch.is_constant() -> {}", nupatch.is_constant());
+                    println!("\tnupatch.has_trim_curve() -> {}", nupatch.has_trim_curve());
+
+                    let sample = nupatch.load_sample(0, &reader)?;
+                    println!("\tnumber of control points: {}", sample.positions.len());
+                    println!("\torder: {} x {}", sample.u_order, sample.v_order);
+                }
+                Schema::FaceSet(face_set) => {
+                    println!("face set schema.");
+                    println!(
+                        "\tface_set.load_exclusivity() -> {:?}",
+                        face_set.load_exclusivity(&reader)?
+                    );
+
+                    let faces = face_set.load_faces_sample(0, &reader)?;
+                    println!("\tnumber of faces: {}", faces.len());
+                }
+                Schema::Camera(camera) => {
+                    println!("camera schema.");
+                    println!("\tcamera.is_constant() -> {}", camera.is_constant());
+
+                    let sample = camera.load_sample(0, &reader)?;
+                    println!("\tfocal length: {}", sample.focal_length);
+                    println!("\tfield of view: {}", sample.field_of_view());
+                }
+                Schema::Light(light) => {
+                    println!("light schema.");
+                    println!("\tlight.is_constant() -> {}", light.is_constant());
+                    println!("\tlight.has_camera() -> {}", light.has_camera());
+                }
+                Schema::Material(material) => {
+                    println!("material schema.");
+                    for target in material.target_names() {
+                        for shader_type in material.shader_type_names(target) {
+                            println!(
+                                "\t{}.{} -> {:?}",
+                                target,
+                                shader_type,
+                                material.shader(target, shader_type)
+                            );
+                        }
+                    }
+                    println!("\tnumber of nodes: {}", material.nodes().len());
+                }
+                Schema::Collections(collections) => {
+                    println!("collections schema.");
+                    for (index, name) in collections.collection_names().iter().enumerate() {
+                        let paths = collections.load_collection_sample(index, 0, &reader)?;
+                        println!("\t{} -> {:?}", name, paths);
+                    }
                 }
             },
             Err(OgawaError::ParsingError(ParsingError::IncompatibleSchema)) => {
@@ -86,7 +166,7 @@ fn main() -> anyhow::Result<()> {
         for i in (0..child_count).rev() {
             let child = current.load_child(
                 i,
-                &mut reader,
+                &reader,
                 &archive.indexed_meta_data,
                 &archive.time_samplings,
             )?;
@@ -0,0 +1,46 @@
+use ogawa_rs::*;
+
+fn main() -> anyhow::Result<()> {
+    let args = std::env::args().collect::<Vec<String>>();
+    anyhow::ensure!(args.len() == 2, "Expecting one filename argument.");
+
+    let file = std::io::BufWriter::new(std::fs::File::create(&args[1])?);
+    let mut archive = ArchiveWriter::new(file)?;
+
+    let time_sampling = archive.add_time_sampling(TimeSampling::uniform(1.0 / 24.0, 0.0));
+
+    let face_counts = [4; 6];
+    let face_indices = [
+        0, 1, 3, 2, 2, 3, 7, 6, 6, 7, 5, 4, 4, 5, 1, 0, 1, 5, 7, 3, 4, 0, 2, 6,
+    ];
+
+    let mut cube = PolyMeshWriter::new("cube", time_sampling);
+    for frame in 0..48 {
+        let height = 1.0 + (frame as f32 / 48.0 * std::f32::consts::TAU).sin() * 0.5;
+        let positions = (0..8)
+            .map(|i| {
+                [
+                    (i & 1) as f32 - 0.5,
+                    ((i >> 1) & 1) as f32 * height,
+                    ((i >> 2) & 1) as f32 - 0.5,
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        cube.add_sample(
+            &PolyMeshSample {
+                positions: &positions,
+                face_indices: &face_indices,
+                face_counts: &face_counts,
+                ..Default::default()
+            },
+            &mut archive,
+        )?;
+    }
+
+    let mut root = ObjectWriter::new_root();
+    root.add_child(cube.into_object_writer()?)?;
+    archive.finish(root)?;
+
+    Ok(())
+}
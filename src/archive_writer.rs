@@ -0,0 +1,286 @@
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::collections::HashMap;
+use std::io::{Seek, SeekFrom, Write};
+
+use crate::chunks::{EMPTY_DATA, EMPTY_GROUP};
+use crate::hash::{murmur3_x64_128, Digest};
+use crate::metadata::{self, MetaData};
+use crate::object_writer::ObjectWriter;
+use crate::pod::PodArray;
+use crate::result::*;
+use crate::time_sampling::{self, TimeSampling};
+
+const OGAWA_MAGIC: [u8; 5] = [0x4f, 0x67, 0x61, 0x77, 0x61];
+const OGAWA_FILE_VERSION: u16 = 1;
+
+const ALEMBIC_OGAWA_FILE_VERSION: u32 = 0;
+const ALEMBIC_LIBRARY_VERSION: u32 = 10709;
+
+pub struct ArchiveWriter<W: Write + Seek> {
+    writer: W,
+    position: u64,
+
+    pub meta_data: MetaData,
+
+    version_data: u64,
+    file_version_data: u64,
+
+    time_samplings: Vec<TimeSampling>,
+    max_samples: Vec<u32>,
+    indexed_meta_data: Vec<String>,
+    written_samples: HashMap<(Digest, usize), u64>,
+}
+
+impl<W: Write + Seek> ArchiveWriter<W> {
+    pub fn new(mut writer: W) -> Result<Self> {
+        writer.seek(SeekFrom::Start(0))?;
+
+        writer.write_all(&OGAWA_MAGIC)?;
+        // the frozen byte is only set once the archive has been finished
+        writer.write_u8(0x00)?;
+        writer.write_u16::<LittleEndian>(OGAWA_FILE_VERSION)?;
+        writer.write_u64::<LittleEndian>(EMPTY_GROUP)?;
+
+        let mut meta_data = MetaData::default();
+        meta_data.tokens.insert(
+            "_ai_AlembicVersion".to_owned(),
+            format!("ogawa-rs {}", env!("CARGO_PKG_VERSION")),
+        );
+
+        let mut archive = Self {
+            writer,
+            position: 16,
+
+            meta_data,
+
+            version_data: EMPTY_DATA,
+            file_version_data: EMPTY_DATA,
+
+            time_samplings: vec![TimeSampling::default()],
+            max_samples: vec![0],
+            indexed_meta_data: vec![String::new()],
+            written_samples: HashMap::default(),
+        };
+
+        archive.version_data = archive.write_data(&ALEMBIC_OGAWA_FILE_VERSION.to_le_bytes())?;
+        archive.file_version_data = archive.write_data(&ALEMBIC_LIBRARY_VERSION.to_le_bytes())?;
+
+        Ok(archive)
+    }
+
+    pub fn add_time_sampling(&mut self, time_sampling: TimeSampling) -> u32 {
+        if let Some(index) = self.time_samplings.iter().position(|x| *x == time_sampling) {
+            return index as u32;
+        }
+
+        self.time_samplings.push(time_sampling);
+        self.max_samples.push(0);
+        (self.time_samplings.len() - 1) as u32
+    }
+
+    pub fn time_sampling_count(&self) -> usize {
+        self.time_samplings.len()
+    }
+
+    pub(crate) fn update_max_samples(
+        &mut self,
+        time_sampling_index: u32,
+        sample_count: u32,
+    ) -> Result<()> {
+        let max_samples = self
+            .max_samples
+            .get_mut(time_sampling_index as usize)
+            .ok_or(UserError::OutOfBounds)?;
+        *max_samples = (*max_samples).max(sample_count);
+        Ok(())
+    }
+
+    pub(crate) fn meta_data_index(&mut self, meta_data: &MetaData) -> u8 {
+        let text = meta_data.serialize();
+        if text.len() > 0xff {
+            return 0xff;
+        }
+
+        if let Some(index) = self.indexed_meta_data.iter().position(|x| *x == text) {
+            index as u8
+        } else if self.indexed_meta_data.len() < 0xff {
+            self.indexed_meta_data.push(text);
+            (self.indexed_meta_data.len() - 1) as u8
+        } else {
+            0xff
+        }
+    }
+
+    // Writes a property sample prefixed with its key, returning the data and the key. Samples
+    // with the same contents share a single data chunk across the whole archive.
+    pub(crate) fn write_sample(&mut self, sample: &PodArray) -> Result<(u64, Digest)> {
+        let data = sample.to_le_bytes()?;
+        let key = murmur3_x64_128(&data);
+        if data.is_empty() {
+            return Ok((EMPTY_DATA, key));
+        }
+
+        if let Some(&written) = self.written_samples.get(&(key, data.len())) {
+            return Ok((written, key));
+        }
+
+        let written = self.write_data_parts(&[&key, &data])?;
+        self.written_samples.insert((key, data.len()), written);
+        Ok((written, key))
+    }
+
+    pub(crate) fn write_data(&mut self, data: &[u8]) -> Result<u64> {
+        self.write_data_parts(&[data])
+    }
+
+    fn write_data_parts(&mut self, parts: &[&[u8]]) -> Result<u64> {
+        let size = parts.iter().map(|part| part.len() as u64).sum::<u64>();
+        if size == 0 {
+            return Ok(EMPTY_DATA);
+        }
+
+        let position = self.position;
+        self.writer.write_u64::<LittleEndian>(size)?;
+        for part in parts {
+            self.writer.write_all(part)?;
+        }
+        self.position += 8 + size;
+
+        Ok(position | EMPTY_DATA)
+    }
+
+    pub(crate) fn write_group(&mut self, children: &[u64]) -> Result<u64> {
+        if children.is_empty() {
+            return Ok(EMPTY_GROUP);
+        }
+
+        let position = self.position;
+        self.writer
+            .write_u64::<LittleEndian>(children.len() as u64)?;
+        for &child in children {
+            self.writer.write_u64::<LittleEndian>(child)?;
+        }
+        self.position += 8 + 8 * children.len() as u64;
+
+        Ok(position)
+    }
+
+    pub fn finish(mut self, root: ObjectWriter) -> Result<W> {
+        let (top_object, _, _) = root.write(&mut self)?;
+
+        let meta_data = self.write_data(self.meta_data.serialize().as_bytes())?;
+
+        let time_samplings = {
+            let buffer = time_sampling::write_time_samplings_and_max(
+                &self.time_samplings,
+                &self.max_samples,
+            )?;
+            self.write_data(&buffer)?
+        };
+
+        let indexed_meta_data = {
+            let buffer = metadata::write_indexed_meta_data(&self.indexed_meta_data)?;
+            self.write_data(&buffer)?
+        };
+
+        let root_group = self.write_group(&[
+            self.version_data,
+            self.file_version_data,
+            top_object,
+            meta_data,
+            time_samplings,
+            indexed_meta_data,
+        ])?;
+
+        self.writer.seek(SeekFrom::Start(5))?;
+        self.writer.write_u8(0xff)?;
+        self.writer.seek(SeekFrom::Start(8))?;
+        self.writer.write_u64::<LittleEndian>(root_group)?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pod::U64_TYPE;
+    use crate::property::ScalarPropertyWriter;
+    use crate::Archive;
+    use std::io::Cursor;
+
+    fn meta_data(key: &str, value: &str) -> MetaData {
+        let mut meta_data = MetaData::default();
+        meta_data.tokens.insert(key.to_owned(), value.to_owned());
+        meta_data
+    }
+
+    #[test]
+    fn archive_round_trip() -> Result<()> {
+        let mut writer = ArchiveWriter::new(Cursor::new(vec![]))?;
+        writer
+            .meta_data
+            .tokens
+            .insert("_ai_Application".to_owned(), "tests".to_owned());
+
+        let uniform = TimeSampling::uniform(1.0 / 24.0, 0.5);
+        let acyclic = TimeSampling::acyclic(vec![0.0, 0.25, 2.0]);
+        assert_eq!(writer.add_time_sampling(uniform.clone()), 1);
+        assert_eq!(writer.add_time_sampling(acyclic.clone()), 2);
+        assert_eq!(writer.add_time_sampling(uniform.clone()), 1);
+
+        // metadata longer than 255 bytes can not be indexed and is stored with the object
+        let short = meta_data("schema", "short");
+        let long = meta_data("long", &"x".repeat(300));
+
+        let mut root = ObjectWriter::new_root();
+        let mut property = ScalarPropertyWriter::new("value", U64_TYPE, short.clone(), 2);
+        for value in 0..3 {
+            property.add_sample(&PodArray::U64(vec![value]), &mut writer)?;
+        }
+        root.properties_mut().add_sub_property(property)?;
+        root.add_child(ObjectWriter::new("short", short.clone()))?;
+        root.add_child(ObjectWriter::new("long", long.clone()))?;
+
+        let data = writer.finish(root)?.into_inner();
+        assert_eq!(data[0..5], OGAWA_MAGIC);
+        assert_eq!(data[5], 0xff);
+
+        let archive = Archive::new(&data)?;
+        assert_eq!(archive.alembic_file_version, OGAWA_FILE_VERSION);
+        assert_eq!(archive.version, ALEMBIC_OGAWA_FILE_VERSION);
+        assert_eq!(archive.ogawa_file_version, ALEMBIC_LIBRARY_VERSION);
+        assert_eq!(
+            archive.root_header.meta_data.tokens.get("_ai_Application"),
+            Some(&"tests".to_owned())
+        );
+
+        let time_samplings = archive
+            .time_samplings
+            .iter()
+            .map(|x| x.as_ref().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(time_samplings, [TimeSampling::default(), uniform, acyclic]);
+        assert_eq!(archive.max_samples, [0, 0, 3]);
+
+        let indexed_meta_data = archive
+            .indexed_meta_data
+            .iter()
+            .map(|x| x.tokens.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            indexed_meta_data,
+            [Default::default(), short.tokens.clone()]
+        );
+
+        let root = archive.load_root_object(&data)?;
+        assert_eq!(root.children.len(), 2);
+        assert_eq!(root.children[0].full_name, "/short");
+        assert_eq!(root.children[0].meta_data.tokens, short.tokens);
+        assert_eq!(root.children[1].full_name, "/long");
+        assert_eq!(root.children[1].meta_data.tokens, long.tokens);
+
+        Ok(())
+    }
+}
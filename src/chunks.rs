@@ -1,13 +1,12 @@
 use crate::pod::*;
-use crate::reader::ArchiveReader;
+use crate::reader::{read_u64_at, ArchiveReader};
 use crate::result::*;
 use byteorder::{LittleEndian, ReadBytesExt};
-use std::io::SeekFrom;
 
 const INVALID_GROUP: u64 = 0x7fffffffffffffff;
-const EMPTY_GROUP: u64 = 0x0000000000000000;
+pub(crate) const EMPTY_GROUP: u64 = 0x0000000000000000;
 // const INVALID_DATA: u64 = 0xffffffffffffffff;
-const EMPTY_DATA: u64 = 0x8000000000000000;
+pub(crate) const EMPTY_DATA: u64 = 0x8000000000000000;
 
 pub fn is_group(value: u64) -> bool {
     (value & EMPTY_DATA) == 0
@@ -33,11 +32,7 @@ pub struct GroupChunk {
 }
 
 impl GroupChunk {
-    pub fn load(
-        group_pos: u64,
-        is_light: bool,
-        reader: &mut dyn ArchiveReader,
-    ) -> Result<GroupChunk> {
+    pub fn load(group_pos: u64, is_light: bool, reader: &dyn ArchiveReader) -> Result<GroupChunk> {
         if is_empty_group(group_pos) {
             return Ok(GroupChunk {
                 position: 0,
@@ -46,9 +41,7 @@ impl GroupChunk {
             });
         }
 
-        reader.seek(SeekFrom::Start(group_pos))?;
-
-        let child_count = reader.read_u64::<LittleEndian>()?;
+        let child_count = read_u64_at(reader, group_pos)?;
         if child_count > reader.size() / 8 || child_count == 0 {
             return Ok(GroupChunk {
                 position: group_pos,
@@ -57,13 +50,17 @@ impl GroupChunk {
             });
         }
 
-        // load child info
+        // load child info, "light" groups with many children read them on demand instead of
+        // keeping them around (this is an Ogawa concept, unrelated to light objects)
         let children = if !is_light || child_count < 9 {
-            (0..child_count)
-                .map(|_| reader.read_u64::<LittleEndian>().map_err(|err| err.into()))
-                .collect::<Result<Vec<_>>>()?
+            let mut children = vec![0u64; child_count as usize];
+            let mut buffer = vec![0u8; child_count as usize * 8];
+            reader.read_at(group_pos + 8, &mut buffer)?;
+            buffer
+                .as_slice()
+                .read_u64_into::<LittleEndian>(&mut children)?;
+            children
         } else {
-            // special case for lights
             vec![]
         };
 
@@ -79,14 +76,13 @@ impl GroupChunk {
 
     pub fn load_group(
         &self,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         index: usize,
         is_light: bool,
     ) -> Result<GroupChunk> {
         if self.is_light() {
             if index < (self.child_count as usize) {
-                reader.seek(SeekFrom::Start(self.position + 8 * (index as u64) + 8))?;
-                let child_pos = reader.read_u64::<LittleEndian>()?;
+                let child_pos = read_u64_at(reader, self.position + 8 * (index as u64) + 8)?;
 
                 if (child_pos & EMPTY_DATA) == 0 {
                     Ok(GroupChunk::load(child_pos, is_light, reader)?)
@@ -103,11 +99,10 @@ impl GroupChunk {
         }
     }
 
-    pub fn load_data(&self, reader: &mut dyn ArchiveReader, index: usize) -> Result<DataChunk> {
+    pub fn load_data(&self, reader: &dyn ArchiveReader, index: usize) -> Result<DataChunk> {
         if self.is_light() {
             if index < (self.child_count as usize) {
-                reader.seek(SeekFrom::Start(self.position + 8 * (index as u64) + 8))?;
-                let child_pos = reader.read_u64::<LittleEndian>()?;
+                let child_pos = read_u64_at(reader, self.position + 8 * (index as u64) + 8)?;
                 if (child_pos & EMPTY_DATA) != 0 {
                     Ok(DataChunk::load(child_pos, reader)?)
                 } else {
@@ -131,13 +126,12 @@ pub struct DataChunk {
 }
 
 impl DataChunk {
-    pub fn load(position: u64, reader: &mut dyn ArchiveReader) -> Result<DataChunk> {
+    pub fn load(position: u64, reader: &dyn ArchiveReader) -> Result<DataChunk> {
         let position = address_from_child(position);
 
         let size = if position != 0 {
-            reader.seek(SeekFrom::Start(position))?;
             // TODO(max): return error if the read size is larger than file size
-            reader.read_u64::<LittleEndian>()?
+            read_u64_at(reader, position)?
         } else {
             0
         };
@@ -148,19 +142,26 @@ impl DataChunk {
     pub fn read_pod_array(
         &self,
         data_type: &DataType,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
     ) -> Result<PodArray> {
         if self.size < 16 && self.size != 0 {
             return Err(ParsingError::InvalidAlembicFile.into());
         }
+        if self.size == 0 {
+            return PodArray::empty(data_type.pod_type);
+        }
 
         const DATA_OFFSET: u64 = 16;
 
+        // the whole sample is read at once and decoded from memory
+        let mut data = vec![0u8; (self.size - DATA_OFFSET) as usize];
+        self.read(DATA_OFFSET, reader, &mut data)?;
+        let mut data = data.as_slice();
+
         match data_type.pod_type {
             PodType::String => {
-                let char_count = (self.size - 16) as usize;
-                let mut char_buffer = vec![0u8; char_count];
-                self.read(16, reader, &mut char_buffer)?;
+                let char_count = data.len();
+                let char_buffer = data;
 
                 let mut start_str = 0;
                 let mut strings = vec![];
@@ -175,69 +176,56 @@ impl DataChunk {
                 }
                 Ok(PodArray::String(strings))
             }
-            PodType::WString => todo!(),
-            PodType::Boolean => todo!(),
-            PodType::U8 => {
-                let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<u8>();
-                let mut buffer = vec![0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_exact(&mut buffer)?;
-                Ok(PodArray::U8(buffer))
-            }
+            // wide strings are stored as platform dependent wchar_t arrays
+            PodType::WString => Err(ParsingError::UnsupportedAlembicFile.into()),
+            PodType::Boolean => Ok(PodArray::Boolean(data.iter().map(|&x| x != 0).collect())),
+            PodType::U8 => Ok(PodArray::U8(data.to_vec())),
             PodType::I8 => {
                 let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<i8>();
                 let mut buffer = vec![0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_i8_into(&mut buffer)?;
+                data.read_i8_into(&mut buffer)?;
                 Ok(PodArray::I8(buffer))
             }
             PodType::U16 => {
                 let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<u16>();
                 let mut buffer = vec![0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_u16_into::<LittleEndian>(&mut buffer)?;
+                data.read_u16_into::<LittleEndian>(&mut buffer)?;
                 Ok(PodArray::U16(buffer))
             }
             PodType::I16 => {
                 let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<i16>();
                 let mut buffer = vec![0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_i16_into::<LittleEndian>(&mut buffer)?;
+                data.read_i16_into::<LittleEndian>(&mut buffer)?;
                 Ok(PodArray::I16(buffer))
             }
             PodType::U32 => {
                 let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<u32>();
                 let mut buffer = vec![0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_u32_into::<LittleEndian>(&mut buffer)?;
+                data.read_u32_into::<LittleEndian>(&mut buffer)?;
                 Ok(PodArray::U32(buffer))
             }
             PodType::I32 => {
                 let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<i32>();
                 let mut buffer = vec![0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_i32_into::<LittleEndian>(&mut buffer)?;
+                data.read_i32_into::<LittleEndian>(&mut buffer)?;
                 Ok(PodArray::I32(buffer))
             }
             PodType::U64 => {
                 let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<u64>();
                 let mut buffer = vec![0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_u64_into::<LittleEndian>(&mut buffer)?;
+                data.read_u64_into::<LittleEndian>(&mut buffer)?;
                 Ok(PodArray::U64(buffer))
             }
             PodType::I64 => {
                 let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<i64>();
                 let mut buffer = vec![0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_i64_into::<LittleEndian>(&mut buffer)?;
+                data.read_i64_into::<LittleEndian>(&mut buffer)?;
                 Ok(PodArray::I64(buffer))
             }
             PodType::F16 => {
-                let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<f32>();
+                let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<u16>();
                 let mut buffer = vec![0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_u16_into::<LittleEndian>(&mut buffer)?;
+                data.read_u16_into::<LittleEndian>(&mut buffer)?;
                 let buffer = buffer
                     .into_iter()
                     .map(half::f16::from_bits)
@@ -247,15 +235,13 @@ impl DataChunk {
             PodType::F32 => {
                 let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<f32>();
                 let mut buffer = vec![0.0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_f32_into::<LittleEndian>(&mut buffer)?;
+                data.read_f32_into::<LittleEndian>(&mut buffer)?;
                 Ok(PodArray::F32(buffer))
             }
             PodType::F64 => {
                 let element_count = (self.size - DATA_OFFSET) as usize / std::mem::size_of::<f64>();
                 let mut buffer = vec![0.0; element_count];
-                reader.seek(SeekFrom::Start(self.position + DATA_OFFSET + 8))?;
-                reader.read_f64_into::<LittleEndian>(&mut buffer)?;
+                data.read_f64_into::<LittleEndian>(&mut buffer)?;
                 Ok(PodArray::F64(buffer))
             }
 
@@ -263,29 +249,21 @@ impl DataChunk {
         }
     }
 
-    pub fn read(
-        &self,
-        offset: u64,
-        reader: &mut dyn ArchiveReader,
-        buffer: &mut [u8],
-    ) -> Result<()> {
+    pub fn read(&self, offset: u64, reader: &dyn ArchiveReader, buffer: &mut [u8]) -> Result<()> {
         if self.size == 0 || offset + self.size > reader.size() {
             return Err(ParsingError::InvalidAlembicFile.into());
         }
 
-        reader.seek(SeekFrom::Start(self.position + offset + 8))?;
-        reader.read_exact(buffer)?;
-
-        Ok(())
+        reader.read_at(self.position + offset + 8, buffer)
     }
-    pub fn read_u32(&self, offset: u64, reader: &mut dyn ArchiveReader) -> Result<u32> {
+    pub fn read_u32(&self, offset: u64, reader: &dyn ArchiveReader) -> Result<u32> {
         if self.size != 4 {
             return Err(ParsingError::InvalidAlembicFile.into());
         }
 
-        reader.seek(SeekFrom::Start(self.position + offset))?;
-        let value = reader.read_u32::<LittleEndian>()?;
-        Ok(value)
+        let mut buffer = [0u8; 4];
+        reader.read_at(self.position + offset + 8, &mut buffer)?;
+        Ok(u32::from_le_bytes(buffer))
     }
 }
 
@@ -293,3 +271,33 @@ pub enum Chunk {
     Group(GroupChunk),
     Data(DataChunk),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_strings_are_rejected() {
+        // a 16 byte sample key followed by "a" as a 32 bit wchar_t, after the magic and the
+        // root group position
+        let mut data = vec![0; 16];
+        data.extend_from_slice(&24u64.to_le_bytes());
+        data.extend_from_slice(&[0; 16]);
+        data.extend_from_slice(&[0x61, 0, 0, 0, 0, 0, 0, 0]);
+
+        let chunk = DataChunk {
+            position: 16,
+            size: 24,
+        };
+        let data_type = DataType {
+            pod_type: PodType::WString,
+            extent: 1,
+        };
+        assert!(matches!(
+            chunk.read_pod_array(&data_type, &data),
+            Err(OgawaError::ParsingError(
+                ParsingError::UnsupportedAlembicFile
+            ))
+        ));
+    }
+}
@@ -1,12 +1,15 @@
 #![doc = include_str!("../README.md")]
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use byteorder::{LittleEndian, ReadBytesExt};
 
+mod archive_writer;
 mod chunks;
+mod hash;
 mod metadata;
 mod object_reader;
+mod object_writer;
 mod pod;
 mod property;
 mod reader;
@@ -14,15 +17,24 @@ mod result;
 mod schemas;
 mod time_sampling;
 
+pub use archive_writer::ArchiveWriter;
 pub use chunks::*;
-use metadata::MetaData;
+pub use metadata::MetaData;
 pub use object_reader::{ObjectHeader, ObjectReader};
+pub use object_writer::ObjectWriter;
 pub use pod::*;
 pub use property::*;
 pub use reader::{ArchiveReader, FileReader, MemMappedReader};
 pub use result::{InternalError, OgawaError, ParsingError, Result, UserError};
-pub use schemas::{BasisType, CurvePeriodicity, CurveType, CurvesSchema, Schema, TopologyVariance};
-pub use time_sampling::{TimeSampling, TimeSamplingType};
+pub use schemas::{
+    BasisType, CameraSample, CameraSchema, CollectionsSchema, CurvePeriodicity, CurveType,
+    CurvesSample, CurvesSchema, CurvesWriter, FaceSetExclusivity, FaceSetSchema, GeomParam,
+    GeometryScope, LightSchema, MaterialConnection, MaterialInterfaceMapping, MaterialNode,
+    MaterialSchema, MaterialTerminal, NuPatchSample, NuPatchSchema, NuPatchTrimCurve, PointsSchema,
+    PolyMeshSample, PolyMeshWriter, Schema, SubDSchema, TopologyVariance, XformOp,
+    XformOperationType, XformSample, XformWriter,
+};
+pub use time_sampling::{SampleSelector, TimeSampling, TimeSamplingType};
 
 pub struct Archive {
     pub alembic_file_version: u16,
@@ -32,26 +44,27 @@ pub struct Archive {
     pub root_group: GroupChunk,
     pub root_header: ObjectHeader,
 
-    pub time_samplings: Vec<Rc<TimeSampling>>,
+    pub time_samplings: Vec<Arc<TimeSampling>>,
     pub max_samples: Vec<i64>,
     pub indexed_meta_data: Vec<MetaData>,
 }
 
 impl Archive {
-    pub fn new(reader: &mut dyn ArchiveReader) -> Result<Self> {
-        let mut magic = vec![0; 5];
-        reader.read_exact(&mut magic)?;
+    pub fn new(reader: &dyn ArchiveReader) -> Result<Self> {
+        let mut header = [0u8; 16];
+        reader.read_at(0, &mut header)?;
+        let (magic, mut header) = header.split_at(5);
 
         if magic != [0x4f, 0x67, 0x61, 0x77, 0x61] {
             return Err(ParsingError::UnsupportedAlembicFile.into());
         }
 
-        let _frozen = reader.read_u8()? == 0xff;
-        let alembic_file_version = reader.read_u16::<LittleEndian>()?;
+        let _frozen = header.read_u8()? == 0xff;
+        let alembic_file_version = header.read_u16::<LittleEndian>()?;
         if alembic_file_version >= 9999 {
             return Err(ParsingError::UnsupportedAlembicFile.into());
         }
-        let group_pos = reader.read_u64::<LittleEndian>()?;
+        let group_pos = header.read_u64::<LittleEndian>()?;
 
         let root_group = GroupChunk::load(group_pos, false, reader)?;
 
@@ -78,11 +91,15 @@ impl Archive {
 
         let meta_data = {
             let data = root_group.load_data(reader, 3)?;
-            let mut buffer = vec![0u8; data.size as usize];
-            data.read(0, reader, &mut buffer)?;
-            let text = String::from_utf8(buffer).map_err(ParsingError::FromUtf8Error)?;
-
-            MetaData::deserialize(&text)
+            if data.size == 0 {
+                MetaData::default()
+            } else {
+                let mut buffer = vec![0u8; data.size as usize];
+                data.read(0, reader, &mut buffer)?;
+                let text = String::from_utf8(buffer).map_err(ParsingError::FromUtf8Error)?;
+
+                MetaData::deserialize(&text)
+            }
         };
 
         let (time_samplings, max_samples) = {
@@ -115,7 +132,7 @@ impl Archive {
         })
     }
 
-    pub fn load_root_object(&self, reader: &mut dyn ArchiveReader) -> Result<ObjectReader> {
+    pub fn load_root_object(&self, reader: &dyn ArchiveReader) -> Result<ObjectReader> {
         let group = self.root_group.load_group(reader, 2, false)?;
         ObjectReader::new(
             group,
@@ -126,4 +143,76 @@ impl Archive {
             self.root_header.clone(),
         )
     }
+
+    // Loads the object with the given full name, like "/group/mesh".
+    pub fn load_object(&self, full_name: &str, reader: &dyn ArchiveReader) -> Result<ObjectReader> {
+        let mut object = self.load_root_object(reader)?;
+        for name in full_name.split('/').filter(|name| !name.is_empty()) {
+            let index = *object
+                .child_map
+                .get(name)
+                .ok_or(UserError::InvalidParameter)?;
+            object =
+                object.load_child(index, reader, &self.indexed_meta_data, &self.time_samplings)?;
+        }
+        Ok(object)
+    }
+
+    // Returns the matrix that places the object at `full_name` in world space at `time`, taking
+    // the transforms of all its ancestors into account.
+    pub fn load_world_matrix(
+        &self,
+        full_name: &str,
+        time: f64,
+        reader: &dyn ArchiveReader,
+    ) -> Result<[[f64; 4]; 4]> {
+        schemas::load_world_matrix(self, full_name, time, reader)
+    }
+
+    // Returns the world space bounds of the object at `full_name` and all of its descendants at
+    // `time`, the box is empty when none of them have bounds.
+    pub fn load_bounds(
+        &self,
+        full_name: &str,
+        time: f64,
+        reader: &dyn ArchiveReader,
+    ) -> Result<BoundingBox> {
+        schemas::load_hierarchy_bounds(self, full_name, time, reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemas::{BaseGeomSchema, PolyMeshSchema, XformSchema};
+
+    // archives are meant to be read from several threads at once
+    fn _assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn readers_are_send_and_sync() {
+        _assert_send_sync::<Archive>();
+        _assert_send_sync::<ObjectReader>();
+        _assert_send_sync::<PropertyReader>();
+        _assert_send_sync::<ArrayPropertyReader>();
+        _assert_send_sync::<ScalarPropertyReader>();
+        _assert_send_sync::<CompoundPropertyReader>();
+        _assert_send_sync::<FileReader>();
+        _assert_send_sync::<MemMappedReader>();
+
+        _assert_send_sync::<Schema>();
+        _assert_send_sync::<BaseGeomSchema>();
+        _assert_send_sync::<CameraSchema>();
+        _assert_send_sync::<CollectionsSchema>();
+        _assert_send_sync::<CurvesSchema>();
+        _assert_send_sync::<FaceSetSchema>();
+        _assert_send_sync::<GeomParam>();
+        _assert_send_sync::<LightSchema>();
+        _assert_send_sync::<MaterialSchema>();
+        _assert_send_sync::<NuPatchSchema>();
+        _assert_send_sync::<PointsSchema>();
+        _assert_send_sync::<PolyMeshSchema>();
+        _assert_send_sync::<SubDSchema>();
+        _assert_send_sync::<XformSchema>();
+    }
 }
@@ -1,8 +1,8 @@
 use crate::reader::ArchiveReader;
 use crate::result::*;
-use byteorder::ReadBytesExt;
+use byteorder::{ReadBytesExt, WriteBytesExt};
 use std::collections::BTreeMap;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::Read;
 
 #[derive(Debug, Clone, Default)]
 pub struct MetaData {
@@ -56,9 +56,12 @@ impl MetaData {
 
 pub(crate) fn read_indexed_meta_data(
     data: &crate::DataChunk,
-    reader: &mut dyn ArchiveReader,
+    reader: &dyn ArchiveReader,
 ) -> Result<Vec<MetaData>> {
     let mut output = vec![MetaData::default()];
+    if data.size == 0 {
+        return Ok(output);
+    }
 
     let mut buffer = vec![0; data.size as usize];
     data.read(0, reader, &mut buffer)?;
@@ -71,19 +74,27 @@ pub(crate) fn read_indexed_meta_data(
 
         let meta_data_size = buffer.read_u8()?;
 
-        let meta_data = if buffer.position() + meta_data_size as u64 == data.size {
-            buffer.seek(SeekFrom::Current(meta_data_size as i64))?;
-            MetaData {
-                tokens: BTreeMap::new(),
-            }
-        } else {
-            let mut string_buffer = vec![0u8; meta_data_size as usize];
-            buffer.read_exact(&mut string_buffer)?;
-            let text = String::from_utf8(string_buffer).map_err(ParsingError::FromUtf8Error)?;
-            MetaData::deserialize(&text)
-        };
-        output.push(meta_data);
+        if buffer.position() + meta_data_size as u64 > data.size {
+            return Err(ParsingError::InvalidAlembicFile.into());
+        }
+
+        let mut string_buffer = vec![0u8; meta_data_size as usize];
+        buffer.read_exact(&mut string_buffer)?;
+        let text = String::from_utf8(string_buffer).map_err(ParsingError::FromUtf8Error)?;
+        output.push(MetaData::deserialize(&text));
     }
 
     Ok(output)
 }
+
+pub(crate) fn write_indexed_meta_data(indexed_meta_data: &[String]) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+
+    // the first entry is the implicit empty meta data and is never stored
+    for meta_data in indexed_meta_data.iter().skip(1) {
+        buffer.write_u8(meta_data.len() as u8)?;
+        buffer.extend_from_slice(meta_data.as_bytes());
+    }
+
+    Ok(buffer)
+}
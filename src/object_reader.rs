@@ -1,7 +1,7 @@
 use crate::reader::{ArchiveReader, StringReader};
 use byteorder::{LittleEndian, ReadBytesExt};
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use crate::chunks::*;
 use crate::metadata::*;
@@ -28,9 +28,9 @@ impl ObjectReader {
     pub fn new(
         group: GroupChunk,
         parent_name: &str,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         indexed_meta_data: &[MetaData],
-        time_samplings: &[Rc<TimeSampling>],
+        time_samplings: &[Arc<TimeSampling>],
         header: ObjectHeader,
     ) -> Result<Self> {
         let child_count = group.child_count as usize;
@@ -84,9 +84,9 @@ impl ObjectReader {
     pub fn load_child(
         &self,
         index: usize,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         indexed_meta_data: &[MetaData],
-        time_samplings: &[Rc<TimeSampling>],
+        time_samplings: &[Arc<TimeSampling>],
     ) -> Result<ObjectReader> {
         let parent_group = &self.group;
         let child_group = parent_group.load_group(reader, index + 1, false)?;
@@ -110,7 +110,7 @@ fn read_object_headers(
     group: &GroupChunk,
     index: usize,
     parent_name: &str,
-    reader: &mut dyn ArchiveReader,
+    reader: &dyn ArchiveReader,
     indexed_meta_data: &[MetaData],
 ) -> Result<Vec<ObjectHeader>> {
     let data = group.load_data(reader, index)?;
@@ -0,0 +1,105 @@
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+
+use crate::archive_writer::ArchiveWriter;
+use crate::hash::{murmur3_x64_128, Digest};
+use crate::metadata::*;
+use crate::property::*;
+use crate::result::*;
+
+#[derive(Debug)]
+pub struct ObjectWriter {
+    pub name: String,
+    pub meta_data: MetaData,
+    properties: CompoundPropertyWriter,
+    children: Vec<ObjectWriter>,
+    child_map: HashMap<String, usize>,
+}
+
+impl ObjectWriter {
+    pub fn new(name: &str, meta_data: MetaData) -> Self {
+        Self {
+            name: name.to_owned(),
+            meta_data,
+            properties: CompoundPropertyWriter::new("", MetaData::default()),
+            children: vec![],
+            child_map: HashMap::default(),
+        }
+    }
+
+    // The name and meta data of the root object are not stored, the archive meta data is
+    // used instead.
+    pub fn new_root() -> Self {
+        Self::new("ABC", MetaData::default())
+    }
+
+    pub fn properties(&self) -> &CompoundPropertyWriter {
+        &self.properties
+    }
+    pub fn properties_mut(&mut self) -> &mut CompoundPropertyWriter {
+        &mut self.properties
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.children.len()
+    }
+    pub fn find_child_index(&self, name: &str) -> Option<usize> {
+        self.child_map.get(name).copied()
+    }
+    pub fn add_child(&mut self, child: ObjectWriter) -> Result<usize> {
+        if child.name.is_empty()
+            || child.name.contains('/')
+            || self.child_map.contains_key(&child.name)
+        {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        let index = self.children.len();
+        self.child_map.insert(child.name.clone(), index);
+        self.children.push(child);
+        Ok(index)
+    }
+    pub fn child_mut(&mut self, index: usize) -> Option<&mut ObjectWriter> {
+        self.children.get_mut(index)
+    }
+
+    // Returns the group of the object, the hash of its properties and the hash of its children.
+    pub(crate) fn write<W: Write + Seek>(
+        self,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<(u64, Digest, Digest)> {
+        let mut groups = vec![];
+
+        let (_, properties_group, properties_digest) = self.properties.write(archive)?;
+        groups.push(properties_group);
+
+        let mut buffer = vec![];
+        let mut child_digests = vec![];
+        for child in self.children {
+            let meta_data_index = archive.meta_data_index(&child.meta_data);
+
+            buffer.write_u32::<LittleEndian>(child.name.len() as u32)?;
+            buffer.extend_from_slice(child.name.as_bytes());
+            buffer.write_u8(meta_data_index)?;
+            if meta_data_index == 0xff {
+                let meta_data = child.meta_data.serialize();
+                buffer.write_u32::<LittleEndian>(meta_data.len() as u32)?;
+                buffer.extend_from_slice(meta_data.as_bytes());
+            }
+
+            let (group, properties_digest, children_digest) = child.write(archive)?;
+            groups.push(group);
+            child_digests.extend_from_slice(&properties_digest);
+            child_digests.extend_from_slice(&children_digest);
+        }
+
+        let children_digest = murmur3_x64_128(&[buffer.as_slice(), &child_digests].concat());
+        buffer.extend_from_slice(&properties_digest);
+        buffer.extend_from_slice(&children_digest);
+        groups.push(archive.write_data(&buffer)?);
+
+        let group = archive.write_group(&groups)?;
+        Ok((group, properties_digest, children_digest))
+    }
+}
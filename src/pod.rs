@@ -8,6 +8,88 @@ pub struct BoundingBox {
     pub max: [f64; 3],
 }
 
+impl BoundingBox {
+    pub fn empty() -> Self {
+        Self {
+            min: [f64::MAX; 3],
+            max: [f64::MIN; 3],
+        }
+    }
+
+    pub fn from_points(points: &[[f32; 3]]) -> Self {
+        let mut bounds = Self::empty();
+        for point in points {
+            for (axis, &value) in point.iter().enumerate() {
+                bounds.min[axis] = bounds.min[axis].min(value as f64);
+                bounds.max[axis] = bounds.max[axis].max(value as f64);
+            }
+        }
+        bounds
+    }
+
+    // Boxes that contain nothing have their minimum above their maximum.
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|axis| self.min[axis] > self.max[axis])
+    }
+
+    pub fn union(&self, other: &BoundingBox) -> Self {
+        let mut bounds = self.clone();
+        for axis in 0..3 {
+            bounds.min[axis] = bounds.min[axis].min(other.min[axis]);
+            bounds.max[axis] = bounds.max[axis].max(other.max[axis]);
+        }
+        bounds
+    }
+
+    // Transforms the corners of the box by a row vector matrix and returns the box around them.
+    pub fn transform(&self, matrix: &[[f64; 4]; 4]) -> Self {
+        if self.is_empty() {
+            return self.clone();
+        }
+
+        let mut bounds = Self::empty();
+        for corner in 0..8 {
+            let point: [f64; 3] = std::array::from_fn(|axis| {
+                if corner & (1 << axis) == 0 {
+                    self.min[axis]
+                } else {
+                    self.max[axis]
+                }
+            });
+            let point: [f64; 3] = std::array::from_fn(|axis| {
+                (0..3).map(|i| point[i] * matrix[i][axis]).sum::<f64>() + matrix[3][axis]
+            });
+            bounds = bounds.union(&Self {
+                min: point,
+                max: point,
+            });
+        }
+        bounds
+    }
+
+    pub(crate) fn from_pod_array(pod_array: PodArray) -> Result<Self> {
+        match pod_array {
+            PodArray::F64(array) if array.len() == 6 => Ok(Self {
+                min: [array[0], array[1], array[2]],
+                max: [array[3], array[4], array[5]],
+            }),
+            PodArray::F64(_) => Err(ParsingError::InvalidAlembicFile.into()),
+            _ => Err(InternalError::Unreachable.into()),
+        }
+    }
+
+    pub(crate) fn to_pod_array(&self) -> PodArray {
+        PodArray::F64(vec![
+            self.min[0],
+            self.min[1],
+            self.min[2],
+            self.max[0],
+            self.max[1],
+            self.max[2],
+        ])
+    }
+}
+
 #[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PodType {
@@ -63,7 +145,7 @@ impl TryFrom<u32> for PodType {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PodArray {
     Boolean(Vec<bool>),
     U8(Vec<u8>),
@@ -86,12 +168,101 @@ pub enum PodArray {
     WString(Vec<String>),
 }
 
+impl PodArray {
+    pub fn pod_type(&self) -> PodType {
+        match self {
+            PodArray::Boolean(_) => PodType::Boolean,
+            PodArray::U8(_) => PodType::U8,
+            PodArray::I8(_) => PodType::I8,
+            PodArray::U16(_) => PodType::U16,
+            PodArray::I16(_) => PodType::I16,
+            PodArray::U32(_) => PodType::U32,
+            PodArray::I32(_) => PodType::I32,
+            PodArray::U64(_) => PodType::U64,
+            PodArray::I64(_) => PodType::I64,
+            PodArray::F16(_) => PodType::F16,
+            PodArray::F32(_) => PodType::F32,
+            PodArray::F64(_) => PodType::F64,
+            PodArray::String(_) => PodType::String,
+            PodArray::WString(_) => PodType::WString,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            PodArray::Boolean(array) => array.len(),
+            PodArray::U8(array) => array.len(),
+            PodArray::I8(array) => array.len(),
+            PodArray::U16(array) => array.len(),
+            PodArray::I16(array) => array.len(),
+            PodArray::U32(array) => array.len(),
+            PodArray::I32(array) => array.len(),
+            PodArray::U64(array) => array.len(),
+            PodArray::I64(array) => array.len(),
+            PodArray::F16(array) => array.len(),
+            PodArray::F32(array) => array.len(),
+            PodArray::F64(array) => array.len(),
+            PodArray::String(array) => array.len(),
+            PodArray::WString(array) => array.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn empty(pod_type: PodType) -> Result<Self> {
+        Ok(match pod_type {
+            PodType::Boolean => PodArray::Boolean(vec![]),
+            PodType::U8 => PodArray::U8(vec![]),
+            PodType::I8 => PodArray::I8(vec![]),
+            PodType::U16 => PodArray::U16(vec![]),
+            PodType::I16 => PodArray::I16(vec![]),
+            PodType::U32 => PodArray::U32(vec![]),
+            PodType::I32 => PodArray::I32(vec![]),
+            PodType::U64 => PodArray::U64(vec![]),
+            PodType::I64 => PodArray::I64(vec![]),
+            PodType::F16 => PodArray::F16(vec![]),
+            PodType::F32 => PodArray::F32(vec![]),
+            PodType::F64 => PodArray::F64(vec![]),
+            PodType::String => PodArray::String(vec![]),
+            PodType::WString => PodArray::WString(vec![]),
+            PodType::Unknown => return Err(UserError::InvalidParameter.into()),
+        })
+    }
+
+    // Serializes the elements the way they are stored in a data chunk, without the sample key.
+    pub(crate) fn to_le_bytes(&self) -> Result<Vec<u8>> {
+        Ok(match self {
+            PodArray::Boolean(array) => array.iter().map(|&x| x as u8).collect(),
+            PodArray::U8(array) => array.clone(),
+            PodArray::I8(array) => array.iter().map(|&x| x as u8).collect(),
+            PodArray::U16(array) => array.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            PodArray::I16(array) => array.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            PodArray::U32(array) => array.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            PodArray::I32(array) => array.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            PodArray::U64(array) => array.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            PodArray::I64(array) => array.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            PodArray::F16(array) => array.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            PodArray::F32(array) => array.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            PodArray::F64(array) => array.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            PodArray::String(array) => array
+                .iter()
+                .flat_map(|x| x.as_bytes().iter().copied().chain(std::iter::once(0)))
+                .collect(),
+            PodArray::WString(_) => return Err(UserError::InvalidParameter.into()),
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DataType {
     pub pod_type: PodType,
     pub extent: u8,
 }
 
+// `usize::is_multiple_of` is not available on our MSRV.
+#[allow(clippy::manual_is_multiple_of)]
 pub fn chunk_vector_by_2<T>(mut vector: Vec<T>) -> Result<Vec<[T; 2]>, InternalError> {
     const CHUNK_BY: usize = 2;
     let raw_ptr = vector.as_mut_ptr();
@@ -112,6 +283,7 @@ pub fn chunk_vector_by_2<T>(mut vector: Vec<T>) -> Result<Vec<[T; 2]>, InternalE
     Ok(vector)
 }
 
+#[allow(clippy::manual_is_multiple_of)]
 pub fn chunk_vector_by_3<T>(mut vector: Vec<T>) -> Result<Vec<[T; 3]>, InternalError> {
     const CHUNK_BY: usize = 3;
     let raw_ptr = vector.as_mut_ptr();
@@ -133,6 +305,7 @@ pub fn chunk_vector_by_3<T>(mut vector: Vec<T>) -> Result<Vec<[T; 3]>, InternalE
     Ok(vector)
 }
 
+#[allow(clippy::manual_is_multiple_of)]
 pub fn chunk_vector_by_4<T>(mut vector: Vec<T>) -> Result<Vec<[T; 4]>, InternalError> {
     const CHUNK_BY: usize = 4;
     let raw_ptr = vector.as_mut_ptr();
@@ -164,6 +337,21 @@ pub const U8_TYPE: DataType = DataType {
     extent: 1,
 };
 
+pub const U32_TYPE: DataType = DataType {
+    pod_type: PodType::U32,
+    extent: 1,
+};
+
+pub const U64_TYPE: DataType = DataType {
+    pod_type: PodType::U64,
+    extent: 1,
+};
+
+pub const STRING_TYPE: DataType = DataType {
+    pod_type: PodType::String,
+    extent: 1,
+};
+
 pub const I32_TYPE: DataType = DataType {
     pod_type: PodType::I32,
     extent: 1,
@@ -198,6 +386,11 @@ pub const F32X4_TYPE: DataType = DataType {
     extent: 4,
 };
 
+pub const F64_TYPE: DataType = DataType {
+    pod_type: PodType::F64,
+    extent: 1,
+};
+
 pub const BOX_TYPE: DataType = DataType {
     pod_type: PodType::F64,
     extent: 6,
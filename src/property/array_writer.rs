@@ -0,0 +1,211 @@
+use super::{PropertyHeader, PropertyType, PropertyWriter};
+use crate::archive_writer::ArchiveWriter;
+use crate::chunks::EMPTY_DATA;
+use crate::hash::{murmur3_x64_128, Digest};
+use crate::metadata::MetaData;
+use crate::pod::*;
+use crate::result::*;
+use std::io::{Seek, Write};
+
+#[derive(Debug)]
+pub struct ArrayPropertyWriter {
+    pub header: PropertyHeader,
+    samples: Vec<u64>,
+    keys: Vec<Digest>,
+    previous_sample: Option<(u64, Digest)>,
+    previous_len: Option<usize>,
+}
+
+impl ArrayPropertyWriter {
+    pub fn new(
+        name: &str,
+        data_type: DataType,
+        meta_data: MetaData,
+        time_sampling_index: u32,
+    ) -> Self {
+        let mut header = PropertyHeader::new(
+            name,
+            PropertyType::Array,
+            data_type,
+            meta_data,
+            time_sampling_index,
+        );
+        header.is_scalar_like = true;
+
+        Self {
+            header,
+            samples: vec![],
+            keys: vec![],
+            previous_sample: None,
+            previous_len: None,
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.header.name
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.header.next_sample_index
+    }
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn add_sample<W: Write + Seek>(
+        &mut self,
+        sample: &PodArray,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<()> {
+        let extent = self.header.data_type.extent.max(1) as usize;
+        if sample.pod_type() != self.header.data_type.pod_type || sample.len() % extent != 0 {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        archive.update_max_samples(
+            self.header.time_sampling_index,
+            self.header.next_sample_index + 1,
+        )?;
+
+        let (data, key) = archive.write_sample(sample)?;
+
+        // only samples that differ from the previous one are stored, repeated samples at the
+        // end are covered by `last_changed_index`
+        let index = self.header.next_sample_index;
+        match self.previous_sample {
+            Some((_, previous_key)) if previous_key == key => {}
+            previous_sample => {
+                if let Some((previous_data, _)) = previous_sample {
+                    if self.header.first_changed_index != 0 {
+                        for _ in (self.header.last_changed_index + 1)..index {
+                            self.samples.push(previous_data);
+                            self.samples.push(EMPTY_DATA);
+                        }
+                    }
+
+                    if self.header.first_changed_index == 0 {
+                        self.header.first_changed_index = index;
+                    }
+                    self.header.last_changed_index = index;
+                }
+
+                // samples are stored as (data, dimensions) pairs, the dimensions of one
+                // dimensional arrays are implied by the size of the data and are left empty
+                self.samples.push(data);
+                self.samples.push(EMPTY_DATA);
+                self.keys.push(key);
+                self.previous_sample = Some((data, key));
+            }
+        }
+
+        self.header.is_scalar_like &= sample.len() == extent;
+        self.header.is_homogenous &= !matches!(self.previous_len, Some(len) if len != sample.len());
+        self.previous_len = Some(sample.len());
+        self.header.next_sample_index += 1;
+
+        Ok(())
+    }
+
+    pub(crate) fn write<W: Write + Seek>(
+        self,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<(PropertyHeader, u64, Digest)> {
+        let group = archive.write_group(&self.samples)?;
+        let digest = murmur3_x64_128(&self.keys.concat());
+
+        Ok((self.header, group, digest))
+    }
+}
+
+impl std::convert::TryFrom<PropertyWriter> for ArrayPropertyWriter {
+    type Error = UserError;
+    fn try_from(writer: PropertyWriter) -> Result<Self, Self::Error> {
+        if let PropertyWriter::Array(w) = writer {
+            Ok(w)
+        } else {
+            Err(UserError::InvalidParameter)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{ArrayPropertyReader, ScalarPropertyReader, ScalarPropertyWriter};
+    use crate::{Archive, ObjectWriter};
+    use std::io::Cursor;
+
+    #[test]
+    fn repeated_samples_are_shared() -> Result<()> {
+        let a = PodArray::I32(vec![1, 2, 3]);
+        let b = PodArray::I32(vec![4, 5]);
+        let changing_samples = [&a, &a, &b, &b, &a, &a];
+        let constant_samples = [&a, &a, &a];
+
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut root = ObjectWriter::new_root();
+        for (name, samples) in [
+            ("changing", &changing_samples[..]),
+            ("constant", &constant_samples[..]),
+        ] {
+            let mut array = ArrayPropertyWriter::new(name, I32_TYPE, MetaData::default(), 0);
+            for sample in samples {
+                array.add_sample(sample, &mut archive)?;
+            }
+            root.properties_mut().add_sub_property(array)?;
+        }
+
+        let mut scalar = ScalarPropertyWriter::new("scalar", I32_TYPE, MetaData::default(), 0);
+        for value in [1, 1, 2, 2, 1, 1] {
+            scalar.add_sample(&PodArray::I32(vec![value]), &mut archive)?;
+        }
+        root.properties_mut().add_sub_property(scalar)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let root = archive.load_root_object(&data)?;
+        let properties = root.properties().ok_or(UserError::InvalidParameter)?;
+
+        // samples before the first change are skipped, the ones between changes are filled
+        // in and trailing repeats are covered by the last changed index
+        let changing: ArrayPropertyReader = properties
+            .load_sub_property_by_name("changing", &data, &archive)?
+            .ok_or(UserError::InvalidParameter)?
+            .try_into()?;
+        assert_eq!(changing.sample_count(), 6);
+        assert_eq!(changing.group.child_count, 8);
+        assert_eq!(
+            (
+                changing.header.first_changed_index,
+                changing.header.last_changed_index
+            ),
+            (2, 4)
+        );
+        for (index, sample) in changing_samples.into_iter().enumerate() {
+            assert_eq!(changing.load_sample(index as u32, &data)?, *sample);
+        }
+
+        let constant: ArrayPropertyReader = properties
+            .load_sub_property_by_name("constant", &data, &archive)?
+            .ok_or(UserError::InvalidParameter)?
+            .try_into()?;
+        assert!(constant.is_constant());
+        assert_eq!(constant.group.child_count, 2);
+        for index in 0..3 {
+            assert_eq!(constant.load_sample(index, &data)?, a);
+        }
+
+        // identical samples share a single data chunk across properties
+        assert_eq!(changing.group.children[0], constant.group.children[0]);
+
+        let scalar: ScalarPropertyReader = properties
+            .load_sub_property_by_name("scalar", &data, &archive)?
+            .ok_or(UserError::InvalidParameter)?
+            .try_into()?;
+        assert_eq!(scalar.group.child_count, 4);
+        for (index, value) in [1, 1, 2, 2, 1, 1].into_iter().enumerate() {
+            assert_eq!(
+                scalar.load_sample(index as u32, &data)?,
+                PodArray::I32(vec![value])
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use crate::Archive;
 use byteorder::{LittleEndian, ReadBytesExt};
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::{
     ArrayPropertyReader, PropertyHeader, PropertyReader, PropertyType, ScalarPropertyReader,
@@ -27,9 +27,9 @@ impl CompoundPropertyReader {
     pub fn new(
         group: GroupChunk,
         meta_data: MetaData,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         indexed_meta_data: &[MetaData],
-        time_samplings: &[Rc<TimeSampling>],
+        time_samplings: &[Arc<TimeSampling>],
     ) -> Result<Self> {
         let child_count = group.child_count as usize;
         let mut property_headers = vec![];
@@ -88,7 +88,7 @@ impl CompoundPropertyReader {
     pub fn load_sub_property(
         &self,
         index: usize,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         archive: &Archive,
     ) -> Result<PropertyReader> {
         let header = self
@@ -101,13 +101,17 @@ impl CompoundPropertyReader {
             PropertyType::Array => {
                 PropertyReader::Array(ArrayPropertyReader::new(group, header.clone()))
             }
-            PropertyType::Compound => PropertyReader::Compound(CompoundPropertyReader::new(
-                group,
-                header.meta_data.clone(),
-                reader,
-                &archive.indexed_meta_data,
-                &archive.time_samplings,
-            )?),
+            PropertyType::Compound => {
+                let mut compound = CompoundPropertyReader::new(
+                    group,
+                    header.meta_data.clone(),
+                    reader,
+                    &archive.indexed_meta_data,
+                    &archive.time_samplings,
+                )?;
+                compound.header.name = header.name.clone();
+                PropertyReader::Compound(compound)
+            }
             PropertyType::Scalar => {
                 PropertyReader::Scalar(ScalarPropertyReader::new(group, header.clone()))
             }
@@ -117,7 +121,7 @@ impl CompoundPropertyReader {
     pub fn load_sub_property_by_name(
         &self,
         name: &str,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         archive: &Archive,
     ) -> Result<Option<PropertyReader>> {
         let index = if let Some(index) = self.find_sub_property_index(name) {
@@ -132,7 +136,7 @@ impl CompoundPropertyReader {
     pub fn load_sub_property_by_name_checked(
         &self,
         name: &str,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         archive: &Archive,
         data_type: Option<&DataType>,
     ) -> Result<Option<PropertyReader>> {
@@ -160,9 +164,9 @@ impl CompoundPropertyReader {
 fn read_property_headers(
     group: &GroupChunk,
     index: usize,
-    reader: &mut dyn ArchiveReader,
+    reader: &dyn ArchiveReader,
     indexed_meta_data: &[MetaData],
-    time_samplings: &[Rc<TimeSampling>],
+    time_samplings: &[Arc<TimeSampling>],
 ) -> Result<Vec<PropertyHeader>> {
     let data = group.load_data(reader, index)?;
     if data.size == 0 {
@@ -227,8 +231,8 @@ fn read_property_headers(
                 first_changed_index = 0;
                 last_changed_index = 0;
             } else {
-                first_changed_index = 0;
-                last_changed_index = next_sample_index - 1;
+                first_changed_index = 1;
+                last_changed_index = next_sample_index.saturating_sub(1);
             };
 
             time_sampling_index = if (info & 0x0100) != 0 {
@@ -241,7 +245,7 @@ fn read_property_headers(
                 return Err(ParsingError::InvalidAlembicFile.into());
             }
 
-            time_sampling = Some(Rc::clone(&time_samplings[time_sampling_index as usize]));
+            time_sampling = Some(Arc::clone(&time_samplings[time_sampling_index as usize]));
         }
 
         let name_size = read_u32_with_hint(&mut buffer, size_hint)?;
@@ -0,0 +1,358 @@
+use byteorder::{LittleEndian, WriteBytesExt};
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+
+use super::{PropertyHeader, PropertyType, PropertyWriter};
+use crate::archive_writer::ArchiveWriter;
+use crate::hash::{murmur3_x64_128, Digest};
+use crate::metadata::MetaData;
+use crate::pod::*;
+use crate::result::*;
+
+#[derive(Debug)]
+pub struct CompoundPropertyWriter {
+    pub header: PropertyHeader,
+    sub_properties: Vec<PropertyWriter>,
+    sub_property_map: HashMap<String, usize>,
+}
+
+impl CompoundPropertyWriter {
+    pub fn new(name: &str, meta_data: MetaData) -> Self {
+        Self {
+            header: PropertyHeader::new(
+                name,
+                PropertyType::Compound,
+                DataType {
+                    pod_type: PodType::Unknown,
+                    extent: 0,
+                },
+                meta_data,
+                0,
+            ),
+            sub_properties: vec![],
+            sub_property_map: HashMap::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.header.name
+    }
+
+    pub fn find_sub_property_index(&self, name: &str) -> Option<usize> {
+        self.sub_property_map.get(name).copied()
+    }
+    pub fn sub_property_count(&self) -> usize {
+        self.sub_properties.len()
+    }
+    pub fn add_sub_property(&mut self, property: impl Into<PropertyWriter>) -> Result<usize> {
+        let property = property.into();
+        if property.name().is_empty() || self.sub_property_map.contains_key(property.name()) {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        let index = self.sub_properties.len();
+        self.sub_property_map
+            .insert(property.name().to_owned(), index);
+        self.sub_properties.push(property);
+        Ok(index)
+    }
+    pub fn sub_property_mut(&mut self, index: usize) -> Option<&mut PropertyWriter> {
+        self.sub_properties.get_mut(index)
+    }
+    pub fn sub_property_by_name_mut(&mut self, name: &str) -> Option<&mut PropertyWriter> {
+        let index = self.find_sub_property_index(name)?;
+        self.sub_property_mut(index)
+    }
+
+    pub(crate) fn write<W: Write + Seek>(
+        self,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<(PropertyHeader, u64, Digest)> {
+        let mut children = vec![];
+        let mut headers = vec![];
+        let mut digests = vec![];
+
+        for property in self.sub_properties {
+            let (header, group, digest) = property.write(archive)?;
+            headers.push(header);
+            children.push(group);
+            digests.push(digest);
+        }
+
+        let buffer = write_property_headers(&headers, archive)?;
+        children.push(archive.write_data(&buffer)?);
+        let group = archive.write_group(&children)?;
+
+        let digest = murmur3_x64_128(&[buffer, digests.concat()].concat());
+
+        Ok((self.header, group, digest))
+    }
+}
+
+fn write_property_headers<W: Write + Seek>(
+    headers: &[PropertyHeader],
+    archive: &mut ArchiveWriter<W>,
+) -> Result<Vec<u8>> {
+    let write_u32_with_hint = |buffer: &mut Vec<u8>, size_hint: u32, value: u32| -> Result<()> {
+        match size_hint {
+            0 => buffer.write_u8(value as u8)?,
+            1 => buffer.write_u16::<LittleEndian>(value as u16)?,
+            2 => buffer.write_u32::<LittleEndian>(value)?,
+            _ => return Err(InternalError::Unreachable.into()),
+        }
+        Ok(())
+    };
+
+    let mut buffer = vec![];
+    for header in headers {
+        let meta_data_index = archive.meta_data_index(&header.meta_data);
+        let meta_data = if meta_data_index == 0xff {
+            header.meta_data.serialize()
+        } else {
+            String::new()
+        };
+
+        let max_value = [
+            header.name.len() as u32,
+            meta_data.len() as u32,
+            header.next_sample_index,
+            header.first_changed_index,
+            header.last_changed_index,
+            header.time_sampling_index,
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+        let size_hint = if max_value < 0x100 {
+            0
+        } else if max_value < 0x10000 {
+            1
+        } else {
+            2
+        };
+
+        let mut info = match header.property_type {
+            PropertyType::Compound => 0,
+            PropertyType::Scalar => 1,
+            PropertyType::Array if header.is_scalar_like => 3,
+            PropertyType::Array => 2,
+        };
+        info |= size_hint << 2;
+
+        let is_compound = header.property_type == PropertyType::Compound;
+        let has_changed_indices = if is_compound {
+            false
+        } else if header.first_changed_index == 0 && header.last_changed_index == 0 {
+            info |= 0x0800;
+            false
+        } else {
+            header.first_changed_index != 1
+                || header.last_changed_index != header.next_sample_index - 1
+        };
+
+        if !is_compound {
+            info |= (header.data_type.pod_type as u32 & 0xf) << 4;
+            if header.time_sampling_index != 0 {
+                info |= 0x0100;
+            }
+            if has_changed_indices {
+                info |= 0x0200;
+            }
+            if header.is_homogenous {
+                info |= 0x0400;
+            }
+            info |= (header.data_type.extent as u32) << 12;
+        }
+        info |= (meta_data_index as u32) << 20;
+
+        buffer.write_u32::<LittleEndian>(info)?;
+
+        if !is_compound {
+            write_u32_with_hint(&mut buffer, size_hint, header.next_sample_index)?;
+            if has_changed_indices {
+                write_u32_with_hint(&mut buffer, size_hint, header.first_changed_index)?;
+                write_u32_with_hint(&mut buffer, size_hint, header.last_changed_index)?;
+            }
+            if header.time_sampling_index != 0 {
+                write_u32_with_hint(&mut buffer, size_hint, header.time_sampling_index)?;
+            }
+        }
+
+        write_u32_with_hint(&mut buffer, size_hint, header.name.len() as u32)?;
+        buffer.extend_from_slice(header.name.as_bytes());
+
+        if meta_data_index == 0xff {
+            write_u32_with_hint(&mut buffer, size_hint, meta_data.len() as u32)?;
+            buffer.extend_from_slice(meta_data.as_bytes());
+        }
+    }
+
+    Ok(buffer)
+}
+
+impl std::convert::TryFrom<PropertyWriter> for CompoundPropertyWriter {
+    type Error = UserError;
+    fn try_from(writer: PropertyWriter) -> Result<Self, Self::Error> {
+        if let PropertyWriter::Compound(w) = writer {
+            Ok(w)
+        } else {
+            Err(UserError::InvalidParameter)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property::{ArrayPropertyWriter, CompoundPropertyReader, ScalarPropertyWriter};
+    use crate::{Archive, ArchiveWriter, ObjectWriter, TimeSampling};
+    use std::io::Cursor;
+
+    fn check_header(
+        header: &PropertyHeader,
+        name: &str,
+        property_type: PropertyType,
+        data_type: DataType,
+        next_sample_index: u32,
+        changed_indices: (u32, u32),
+        time_sampling_index: u32,
+    ) {
+        assert_eq!(header.name, name);
+        assert_eq!(header.property_type, property_type);
+        assert_eq!(header.data_type, data_type);
+        assert_eq!(header.next_sample_index, next_sample_index);
+        assert_eq!(
+            (header.first_changed_index, header.last_changed_index),
+            changed_indices
+        );
+        assert_eq!(header.time_sampling_index, time_sampling_index);
+    }
+
+    #[test]
+    fn property_headers_round_trip() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let time_sampling = archive.add_time_sampling(TimeSampling::uniform(0.5, 1.0));
+
+        let mut meta_data = MetaData::default();
+        meta_data
+            .tokens
+            .insert("interpretation".to_owned(), "point".to_owned());
+
+        let mut root = ObjectWriter::new_root();
+
+        // changes once and then repeats, so the changed indices have to be stored
+        let mut scalar =
+            ScalarPropertyWriter::new("scalar", U8_TYPE, meta_data.clone(), time_sampling);
+        for value in [1, 2, 2] {
+            scalar.add_sample(&PodArray::U8(vec![value]), &mut archive)?;
+        }
+        root.properties_mut().add_sub_property(scalar)?;
+
+        // arrays with a single element of the extent are scalar like
+        let mut constant = ArrayPropertyWriter::new("constant", F32X3_TYPE, MetaData::default(), 0);
+        for _ in 0..2 {
+            constant.add_sample(&PodArray::F32(vec![1.0, 2.0, 3.0]), &mut archive)?;
+        }
+        root.properties_mut().add_sub_property(constant)?;
+
+        let mut varying =
+            ArrayPropertyWriter::new("varying", F32X3_TYPE, MetaData::default(), time_sampling);
+        varying.add_sample(&PodArray::F32(vec![0.0; 6]), &mut archive)?;
+        varying.add_sample(&PodArray::F32(vec![1.0; 12]), &mut archive)?;
+        root.properties_mut().add_sub_property(varying)?;
+
+        // long names and sample counts need wider size hints
+        let long_name = "n".repeat(300);
+        let mut long = ScalarPropertyWriter::new(&long_name, U8_TYPE, MetaData::default(), 0);
+        for _ in 0..70000 {
+            long.add_sample(&PodArray::U8(vec![7]), &mut archive)?;
+        }
+        root.properties_mut().add_sub_property(long)?;
+
+        let mut compound = CompoundPropertyWriter::new("compound", meta_data.clone());
+        let mut nested = ArrayPropertyWriter::new("nested", I32_TYPE, MetaData::default(), 0);
+        nested.add_sample(&PodArray::I32(vec![1, 2, 3]), &mut archive)?;
+        compound.add_sub_property(nested)?;
+        root.properties_mut().add_sub_property(compound)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let root = archive.load_root_object(&data)?;
+        let properties = root.properties().ok_or(UserError::InvalidParameter)?;
+        let headers = &properties.property_headers;
+        assert_eq!(headers.len(), 5);
+
+        check_header(
+            &headers[0],
+            "scalar",
+            PropertyType::Scalar,
+            U8_TYPE,
+            3,
+            (1, 1),
+            1,
+        );
+        assert!(headers[0].is_scalar_like);
+        assert!(headers[0].is_homogenous);
+        assert_eq!(headers[0].meta_data.tokens, meta_data.tokens);
+        assert_eq!(
+            headers[0].time_sampling.as_deref(),
+            Some(&TimeSampling::uniform(0.5, 1.0))
+        );
+
+        check_header(
+            &headers[1],
+            "constant",
+            PropertyType::Array,
+            F32X3_TYPE,
+            2,
+            (0, 0),
+            0,
+        );
+        assert!(headers[1].is_scalar_like);
+        assert!(headers[1].is_homogenous);
+
+        check_header(
+            &headers[2],
+            "varying",
+            PropertyType::Array,
+            F32X3_TYPE,
+            2,
+            (1, 1),
+            1,
+        );
+        assert!(!headers[2].is_scalar_like);
+        assert!(!headers[2].is_homogenous);
+
+        check_header(
+            &headers[3],
+            &long_name,
+            PropertyType::Scalar,
+            U8_TYPE,
+            70000,
+            (0, 0),
+            0,
+        );
+        assert!(headers[3].is_scalar_like);
+
+        assert_eq!(headers[4].name, "compound");
+        assert_eq!(headers[4].property_type, PropertyType::Compound);
+        assert_eq!(headers[4].meta_data.tokens, meta_data.tokens);
+
+        let compound: CompoundPropertyReader = properties
+            .load_sub_property(4, &data, &archive)?
+            .try_into()?;
+        assert_eq!(compound.name(), "compound");
+        check_header(
+            &compound.property_headers[0],
+            "nested",
+            PropertyType::Array,
+            I32_TYPE,
+            1,
+            (0, 0),
+            0,
+        );
+        assert!(!compound.property_headers[0].is_scalar_like);
+
+        Ok(())
+    }
+}
@@ -1,16 +1,26 @@
 pub(crate) mod array_reader;
+pub(crate) mod array_writer;
 pub(crate) mod compound_reader;
+pub(crate) mod compound_writer;
 pub(crate) mod scalar_reader;
+pub(crate) mod scalar_writer;
 
-pub(crate) use array_reader::*;
-pub(crate) use compound_reader::*;
-pub(crate) use scalar_reader::*;
+pub use array_reader::*;
+pub use array_writer::*;
+pub use compound_reader::*;
+pub use compound_writer::*;
+pub use scalar_reader::*;
+pub use scalar_writer::*;
 
-use std::rc::Rc;
+use std::io::{Seek, Write};
+use std::sync::Arc;
 
+use crate::archive_writer::ArchiveWriter;
+use crate::hash::Digest;
 use crate::metadata::MetaData;
+use crate::result::*;
 use crate::DataType;
-use crate::TimeSampling;
+use crate::{SampleSelector, TimeSampling};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum PropertyType {
@@ -19,6 +29,7 @@ pub enum PropertyType {
     Scalar,
 }
 
+#[derive(Debug)]
 pub enum PropertyReader {
     Array(ArrayPropertyReader),
     Compound(CompoundPropertyReader),
@@ -34,13 +45,55 @@ impl PropertyReader {
     }
 }
 
+#[derive(Debug)]
+pub enum PropertyWriter {
+    Array(ArrayPropertyWriter),
+    Compound(CompoundPropertyWriter),
+    Scalar(ScalarPropertyWriter),
+}
+impl PropertyWriter {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Array(w) => w.name(),
+            Self::Compound(w) => w.name(),
+            Self::Scalar(w) => w.name(),
+        }
+    }
+
+    pub(crate) fn write<W: Write + Seek>(
+        self,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<(PropertyHeader, u64, Digest)> {
+        match self {
+            Self::Array(w) => w.write(archive),
+            Self::Compound(w) => w.write(archive),
+            Self::Scalar(w) => w.write(archive),
+        }
+    }
+}
+impl From<ArrayPropertyWriter> for PropertyWriter {
+    fn from(writer: ArrayPropertyWriter) -> Self {
+        Self::Array(writer)
+    }
+}
+impl From<CompoundPropertyWriter> for PropertyWriter {
+    fn from(writer: CompoundPropertyWriter) -> Self {
+        Self::Compound(writer)
+    }
+}
+impl From<ScalarPropertyWriter> for PropertyWriter {
+    fn from(writer: ScalarPropertyWriter) -> Self {
+        Self::Scalar(writer)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PropertyHeader {
     pub name: String,
     pub property_type: PropertyType,
     pub meta_data: MetaData,
     pub data_type: DataType,
-    pub time_sampling: Option<Rc<TimeSampling>>,
+    pub time_sampling: Option<Arc<TimeSampling>>,
 
     // friends?
     pub is_scalar_like: bool,
@@ -52,6 +105,37 @@ pub struct PropertyHeader {
 }
 
 impl PropertyHeader {
+    pub(crate) fn new(
+        name: &str,
+        property_type: PropertyType,
+        data_type: DataType,
+        meta_data: MetaData,
+        time_sampling_index: u32,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            property_type,
+            meta_data,
+            data_type,
+            time_sampling: None,
+
+            is_scalar_like: property_type != PropertyType::Array,
+            is_homogenous: true,
+            next_sample_index: 0,
+            first_changed_index: 0,
+            last_changed_index: 0,
+            time_sampling_index,
+        }
+    }
+
+    // Properties without a time sampling use the default one, like Alembic does.
+    pub fn sample_index(&self, sample: SampleSelector) -> u32 {
+        match &self.time_sampling {
+            Some(time_sampling) => sample.sample_index(time_sampling, self.next_sample_index),
+            None => sample.sample_index(&TimeSampling::default(), self.next_sample_index),
+        }
+    }
+
     fn map_index(&self, index: u32) -> usize {
         if index < self.first_changed_index
             || (self.first_changed_index == 0 && self.last_changed_index == 0)
@@ -3,8 +3,7 @@ use crate::chunks::*;
 use crate::pod::*;
 use crate::reader::ArchiveReader;
 use crate::result::*;
-
-pub use std::convert::TryInto;
+use crate::time_sampling::SampleSelector;
 
 #[derive(Debug)]
 pub struct ScalarPropertyReader {
@@ -26,7 +25,15 @@ impl ScalarPropertyReader {
     pub fn sample_count(&self) -> u32 {
         self.header.next_sample_index
     }
-    pub fn load_sample(&self, index: u32, reader: &mut dyn ArchiveReader) -> Result<PodArray> {
+    pub fn sample_index(&self, sample: impl Into<SampleSelector>) -> u32 {
+        self.header.sample_index(sample.into())
+    }
+    pub fn load_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<PodArray> {
+        let index = self.sample_index(sample);
         if index >= self.header.next_sample_index {
             return Err(UserError::OutOfBounds.into());
         }
@@ -35,7 +42,12 @@ impl ScalarPropertyReader {
         let data = self.group.load_data(reader, index)?;
         data.read_pod_array(&self.header.data_type, reader)
     }
-    pub fn sample_size(&self, index: u32, reader: &mut dyn ArchiveReader) -> Result<usize> {
+    pub fn sample_size(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<usize> {
+        let index = self.sample_index(sample);
         if index >= self.header.next_sample_index {
             return Err(UserError::OutOfBounds.into());
         }
@@ -0,0 +1,109 @@
+use super::{PropertyHeader, PropertyType, PropertyWriter};
+use crate::archive_writer::ArchiveWriter;
+use crate::hash::{murmur3_x64_128, Digest};
+use crate::metadata::MetaData;
+use crate::pod::*;
+use crate::result::*;
+use std::io::{Seek, Write};
+
+#[derive(Debug)]
+pub struct ScalarPropertyWriter {
+    pub header: PropertyHeader,
+    samples: Vec<u64>,
+    keys: Vec<Digest>,
+    previous_sample: Option<(u64, Digest)>,
+}
+
+impl ScalarPropertyWriter {
+    pub fn new(
+        name: &str,
+        data_type: DataType,
+        meta_data: MetaData,
+        time_sampling_index: u32,
+    ) -> Self {
+        Self {
+            header: PropertyHeader::new(
+                name,
+                PropertyType::Scalar,
+                data_type,
+                meta_data,
+                time_sampling_index,
+            ),
+            samples: vec![],
+            keys: vec![],
+            previous_sample: None,
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.header.name
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.header.next_sample_index
+    }
+    pub fn add_sample<W: Write + Seek>(
+        &mut self,
+        sample: &PodArray,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<()> {
+        if sample.pod_type() != self.header.data_type.pod_type
+            || sample.len() != self.header.data_type.extent as usize
+        {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        archive.update_max_samples(
+            self.header.time_sampling_index,
+            self.header.next_sample_index + 1,
+        )?;
+
+        let (data, key) = archive.write_sample(sample)?;
+
+        let index = self.header.next_sample_index;
+        match self.previous_sample {
+            Some((_, previous_key)) if previous_key == key => {}
+            previous_sample => {
+                if let Some((previous_data, _)) = previous_sample {
+                    if self.header.first_changed_index != 0 {
+                        for _ in (self.header.last_changed_index + 1)..index {
+                            self.samples.push(previous_data);
+                        }
+                    }
+
+                    if self.header.first_changed_index == 0 {
+                        self.header.first_changed_index = index;
+                    }
+                    self.header.last_changed_index = index;
+                }
+
+                self.samples.push(data);
+                self.keys.push(key);
+                self.previous_sample = Some((data, key));
+            }
+        }
+        self.header.next_sample_index += 1;
+
+        Ok(())
+    }
+
+    pub(crate) fn write<W: Write + Seek>(
+        self,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<(PropertyHeader, u64, Digest)> {
+        let group = archive.write_group(&self.samples)?;
+        let digest = murmur3_x64_128(&self.keys.concat());
+
+        Ok((self.header, group, digest))
+    }
+}
+
+impl std::convert::TryFrom<PropertyWriter> for ScalarPropertyWriter {
+    type Error = UserError;
+    fn try_from(writer: PropertyWriter) -> Result<Self, Self::Error> {
+        if let PropertyWriter::Scalar(w) = writer {
+            Ok(w)
+        } else {
+            Err(UserError::InvalidParameter)
+        }
+    }
+}
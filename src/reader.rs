@@ -1,6 +1,5 @@
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 use std::io::SeekFrom;
 
 use crate::result::{ParsingError, Result};
@@ -16,30 +15,39 @@ impl StringReader for std::io::Cursor<Vec<u8>> {
     }
 }
 
-pub trait ArchiveReader: std::io::Read + std::io::Seek {
+// Readers do not keep a cursor, every read names its own position. This allows a single reader
+// to be shared by many threads at once.
+pub trait ArchiveReader: Send + Sync {
     fn size(&self) -> u64;
+    // Fills the whole buffer with the bytes starting at `offset`.
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<()>;
+}
+
+pub(crate) fn read_u64_at(reader: &dyn ArchiveReader, offset: u64) -> Result<u64> {
+    let mut buffer = [0u8; 8];
+    reader.read_at(offset, &mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
 }
 
 pub struct MemMappedReader {
     _file: File,
-    cursor: std::io::Cursor<memmap2::Mmap>,
+    mmap: memmap2::Mmap,
     size: u64,
 }
 
-impl std::io::Read for MemMappedReader {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        Ok(self.cursor.read(buf).unwrap())
-    }
-}
-impl std::io::Seek for MemMappedReader {
-    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
-        Ok(self.cursor.seek(pos).unwrap())
-    }
-}
 impl ArchiveReader for MemMappedReader {
     fn size(&self) -> u64 {
         self.size
     }
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<()> {
+        let start = usize::try_from(offset).unwrap_or(usize::MAX);
+        let bytes = start
+            .checked_add(buffer.len())
+            .and_then(|end| self.mmap.get(start..end))
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?;
+        buffer.copy_from_slice(bytes);
+        Ok(())
+    }
 }
 
 impl MemMappedReader {
@@ -50,44 +58,66 @@ impl MemMappedReader {
 
         let mmap = unsafe { memmap2::Mmap::map(&file) }?;
 
-        let cursor = std::io::Cursor::new(mmap);
-
         Ok(Self {
             _file: file,
-            cursor,
+            mmap,
             size,
         })
     }
 }
 
 pub struct FileReader {
-    pub file: BufReader<File>,
+    pub file: File,
     pub size: u64,
 }
 
-impl std::io::Read for FileReader {
-    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.file.read(buf)
-    }
-}
-impl std::io::Seek for FileReader {
-    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
-        self.file.seek(pos)
-    }
-}
 impl ArchiveReader for FileReader {
     fn size(&self) -> u64 {
         self.size
     }
+
+    #[cfg(unix)]
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<()> {
+        std::os::unix::fs::FileExt::read_exact_at(&self.file, buffer, offset)?;
+        Ok(())
+    }
+
+    // positional reads on windows move the file cursor, but never depend on it
+    #[cfg(windows)]
+    fn read_at(&self, mut offset: u64, mut buffer: &mut [u8]) -> Result<()> {
+        while !buffer.is_empty() {
+            let read = std::os::windows::fs::FileExt::seek_read(&self.file, buffer, offset)?;
+            if read == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+            }
+            buffer = &mut buffer[read..];
+            offset += read as u64;
+        }
+        Ok(())
+    }
 }
 
 impl FileReader {
-    pub fn new(file: File) -> Result<FileReader> {
-        let mut file = BufReader::new(file);
-
+    pub fn new(mut file: File) -> Result<FileReader> {
         let size = file.seek(SeekFrom::End(0))?;
         file.seek(SeekFrom::Start(0))?;
 
         Ok(FileReader { file, size })
     }
 }
+
+// archives written by the tests are read back straight from memory
+#[cfg(test)]
+impl ArchiveReader for Vec<u8> {
+    fn size(&self) -> u64 {
+        self.len() as u64
+    }
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<()> {
+        let start = offset as usize;
+        let bytes = self
+            .get(start..start + buffer.len())
+            .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::UnexpectedEof))?;
+        buffer.copy_from_slice(bytes);
+        Ok(())
+    }
+}
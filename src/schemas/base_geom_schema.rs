@@ -3,6 +3,7 @@ use crate::pod::*;
 use crate::property::*;
 use crate::reader::ArchiveReader;
 use crate::result::*;
+use crate::time_sampling::SampleSelector;
 use crate::Archive;
 #[derive(Debug)]
 pub struct BaseGeomSchema {
@@ -11,7 +12,7 @@ pub struct BaseGeomSchema {
 impl BaseGeomSchema {
     pub fn new_from_object_reader(
         object: &ObjectReader,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         archive: &Archive,
     ) -> Result<Self> {
         let properties = object
@@ -25,7 +26,7 @@ impl BaseGeomSchema {
 
     pub fn new_from_properties(
         properties: &CompoundPropertyReader,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         archive: &Archive,
     ) -> Result<Self> {
         let self_bounds: ScalarPropertyReader = properties
@@ -41,19 +42,9 @@ impl BaseGeomSchema {
 
     pub fn load_bounds_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<BoundingBox> {
-        let pod_array = self.self_bounds.load_sample(sample_index, reader)?;
-        let pod_array = if let PodArray::F64(array) = pod_array {
-            array
-        } else {
-            return Err(InternalError::Unreachable.into());
-        };
-
-        Ok(BoundingBox {
-            min: [pod_array[0], pod_array[1], pod_array[2]],
-            max: [pod_array[3], pod_array[4], pod_array[5]],
-        })
+        BoundingBox::from_pod_array(self.self_bounds.load_sample(sample, reader)?)
     }
 }
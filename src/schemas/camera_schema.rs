@@ -0,0 +1,397 @@
+use super::geom_param::GeomParam;
+use crate::object_reader::ObjectReader;
+use crate::pod::*;
+use crate::property::*;
+use crate::reader::ArchiveReader;
+use crate::result::*;
+use crate::time_sampling::SampleSelector;
+use crate::Archive;
+pub use std::convert::TryInto;
+
+const CORE_TYPE: DataType = DataType {
+    pod_type: PodType::F64,
+    extent: 16,
+};
+
+// Focal length and focus distance are in millimeters, apertures and film offsets in
+// centimeters, the clipping planes are in scene units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraSample {
+    pub focal_length: f64,
+    pub horizontal_aperture: f64,
+    pub horizontal_film_offset: f64,
+    pub vertical_aperture: f64,
+    pub vertical_film_offset: f64,
+    pub lens_squeeze_ratio: f64,
+
+    pub overscan_left: f64,
+    pub overscan_right: f64,
+    pub overscan_top: f64,
+    pub overscan_bottom: f64,
+
+    pub f_stop: f64,
+    pub focus_distance: f64,
+    pub shutter_open: f64,
+    pub shutter_close: f64,
+
+    pub near_clipping_plane: f64,
+    pub far_clipping_plane: f64,
+}
+
+impl Default for CameraSample {
+    fn default() -> Self {
+        Self {
+            focal_length: 35.0,
+            horizontal_aperture: 3.6,
+            horizontal_film_offset: 0.0,
+            vertical_aperture: 2.4,
+            vertical_film_offset: 0.0,
+            lens_squeeze_ratio: 1.0,
+
+            overscan_left: 0.0,
+            overscan_right: 0.0,
+            overscan_top: 0.0,
+            overscan_bottom: 0.0,
+
+            f_stop: 5.6,
+            focus_distance: 5.0,
+            shutter_open: 0.0,
+            shutter_close: 0.020833333333333332,
+
+            near_clipping_plane: 0.1,
+            far_clipping_plane: 100000.0,
+        }
+    }
+}
+
+impl CameraSample {
+    fn from_core(core: &[f64]) -> Self {
+        Self {
+            focal_length: core[0],
+            horizontal_aperture: core[1],
+            horizontal_film_offset: core[2],
+            vertical_aperture: core[3],
+            vertical_film_offset: core[4],
+            lens_squeeze_ratio: core[5],
+
+            overscan_left: core[6],
+            overscan_right: core[7],
+            overscan_top: core[8],
+            overscan_bottom: core[9],
+
+            f_stop: core[10],
+            focus_distance: core[11],
+            shutter_open: core[12],
+            shutter_close: core[13],
+
+            near_clipping_plane: core[14],
+            far_clipping_plane: core[15],
+        }
+    }
+
+    // Horizontal field of view in degrees.
+    pub fn field_of_view(&self) -> f64 {
+        2.0 * (self.horizontal_aperture * 10.0 / (2.0 * self.focal_length))
+            .atan()
+            .to_degrees()
+    }
+
+    // Vertical field of view in degrees.
+    pub fn vertical_field_of_view(&self) -> f64 {
+        2.0 * (self.vertical_aperture * 10.0 / (2.0 * self.focal_length))
+            .atan()
+            .to_degrees()
+    }
+
+    pub fn aspect_ratio(&self) -> f64 {
+        self.lens_squeeze_ratio * self.horizontal_aperture / self.vertical_aperture
+    }
+
+    // The film window as (left, right, bottom, top) in screen units, like Alembic's
+    // `CameraSample::getScreenWindow`. The squeezed horizontal aperture spans [-1, 1], the
+    // overscans are fractions added to their side.
+    pub fn screen_window(&self) -> (f64, f64, f64, f64) {
+        let aspect_ratio = self.aspect_ratio();
+        let offset_x = self.horizontal_film_offset * 2.0 / self.horizontal_aperture;
+        let offset_y =
+            self.vertical_film_offset * 2.0 / (self.horizontal_aperture * self.lens_squeeze_ratio);
+
+        (
+            -(1.0 + self.overscan_left) + offset_x,
+            1.0 + self.overscan_right + offset_x,
+            -(1.0 + self.overscan_bottom) / aspect_ratio + offset_y,
+            (1.0 + self.overscan_top) / aspect_ratio + offset_y,
+        )
+    }
+
+    // Perspective projection of the screen window from camera space, looking down -Z, to clip
+    // space with a depth range of [-1, 1]. Like the xform matrices it is applied to row vectors.
+    pub fn projection_matrix(&self) -> [[f64; 4]; 4] {
+        let near = self.near_clipping_plane;
+        let far = self.far_clipping_plane;
+
+        // a screen unit is half of the squeezed aperture, converted from centimeters to match
+        // the focal length
+        let scale = near * self.horizontal_aperture * self.lens_squeeze_ratio * 10.0
+            / (2.0 * self.focal_length);
+        let (left, right, bottom, top) = self.screen_window();
+        let (left, right, bottom, top) = (left * scale, right * scale, bottom * scale, top * scale);
+
+        [
+            [2.0 * near / (right - left), 0.0, 0.0, 0.0],
+            [0.0, 2.0 * near / (top - bottom), 0.0, 0.0],
+            [
+                (right + left) / (right - left),
+                (top + bottom) / (top - bottom),
+                -(far + near) / (far - near),
+                -1.0,
+            ],
+            [0.0, 0.0, -2.0 * far * near / (far - near), 0.0],
+        ]
+    }
+}
+
+#[derive(Debug)]
+pub struct CameraSchema {
+    core: ScalarPropertyReader,
+    pub child_bounds: Option<ScalarPropertyReader>,
+    pub arb_geometry_parameters: Option<CompoundPropertyReader>,
+    pub user_properties: Option<CompoundPropertyReader>,
+}
+
+impl CameraSchema {
+    pub fn new_from_object_reader(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let properties = object
+            .properties()
+            .ok_or(ParsingError::IncompatibleSchema)?;
+        let properties: CompoundPropertyReader = properties
+            .load_sub_property(0, reader, archive)?
+            .try_into()?;
+        Self::new_from_properties(&properties, reader, archive)
+    }
+
+    pub fn new_from_properties(
+        properties: &CompoundPropertyReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let core: ScalarPropertyReader = properties
+            .load_sub_property_by_name_checked(".core", reader, archive, Some(&CORE_TYPE))?
+            .ok_or(ParsingError::IncompatibleSchema)?
+            .try_into()?;
+
+        let child_bounds = properties
+            .load_sub_property_by_name_checked(".childBnds", reader, archive, Some(&BOX_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        let arb_geometry_parameters = properties
+            .load_sub_property_by_name(".arbGeomParams", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        let user_properties = properties
+            .load_sub_property_by_name(".userProperties", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        Ok(Self {
+            core,
+            child_bounds,
+            arb_geometry_parameters,
+            user_properties,
+        })
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.core.is_constant()
+    }
+    pub fn sample_count(&self) -> u32 {
+        self.core.sample_count()
+    }
+
+    pub fn load_child_bounds_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<BoundingBox>> {
+        let child_bounds = if let Some(child_bounds) = &self.child_bounds {
+            child_bounds
+        } else {
+            return Ok(None);
+        };
+
+        let pod_array = child_bounds.load_sample(sample, reader)?;
+        BoundingBox::from_pod_array(pod_array).map(Some)
+    }
+
+    pub fn load_arb_geometry_parameters(
+        &self,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<GeomParam>> {
+        GeomParam::load_all_optional(self.arb_geometry_parameters.as_ref(), reader, archive)
+    }
+
+    pub fn load_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<CameraSample> {
+        let pod_array = self.core.load_sample(sample, reader)?;
+        let pod_array = if let PodArray::F64(array) = pod_array {
+            array
+        } else {
+            return Err(InternalError::Unreachable.into());
+        };
+
+        if pod_array.len() != CORE_TYPE.extent as usize {
+            return Err(ParsingError::InvalidAlembicFile.into());
+        }
+
+        Ok(CameraSample::from_core(&pod_array))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive_writer::ArchiveWriter;
+    use crate::metadata::MetaData;
+    use crate::object_writer::ObjectWriter;
+    use crate::schemas::tests::{scalar_property, schema_object};
+    use std::io::Cursor;
+
+    fn assert_near(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-12, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn core_samples_are_decoded() -> Result<()> {
+        let core = (0..16).map(|x| x as f64).collect::<Vec<_>>();
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let properties = vec![scalar_property(
+            ".core",
+            CORE_TYPE,
+            MetaData::default(),
+            0,
+            &[PodArray::F64(core)],
+            &mut archive,
+        )?];
+        let mut root = ObjectWriter::new_root();
+        root.add_child(schema_object("camera", "AbcGeom_Camera_v1", properties)?)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let root = archive.load_root_object(&data)?;
+        let object = root.load_child(
+            0,
+            &data,
+            &archive.indexed_meta_data,
+            &archive.time_samplings,
+        )?;
+        let camera = CameraSchema::new_from_object_reader(&object, &data, &archive)?;
+
+        assert_eq!(camera.sample_count(), 1);
+        assert!(camera.load_child_bounds_sample(0, &data)?.is_none());
+        assert_eq!(
+            camera.load_sample(0, &data)?,
+            CameraSample {
+                focal_length: 0.0,
+                horizontal_aperture: 1.0,
+                horizontal_film_offset: 2.0,
+                vertical_aperture: 3.0,
+                vertical_film_offset: 4.0,
+                lens_squeeze_ratio: 5.0,
+                overscan_left: 6.0,
+                overscan_right: 7.0,
+                overscan_top: 8.0,
+                overscan_bottom: 9.0,
+                f_stop: 10.0,
+                focus_distance: 11.0,
+                shutter_open: 12.0,
+                shutter_close: 13.0,
+                near_clipping_plane: 14.0,
+                far_clipping_plane: 15.0,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fields_of_view() {
+        // a 36mm wide aperture at a focal length of 18mm sees 90 degrees
+        let camera = CameraSample {
+            focal_length: 18.0,
+            ..Default::default()
+        };
+        assert_near(camera.field_of_view(), 90.0);
+        assert_near(
+            camera.vertical_field_of_view(),
+            2.0 * (24.0f64 / 36.0).atan().to_degrees(),
+        );
+        assert_near(camera.aspect_ratio(), 1.5);
+    }
+
+    #[test]
+    fn projection_matrices() {
+        let near = 0.1;
+        let far = 100000.0;
+        let camera = CameraSample::default();
+        let projection = camera.projection_matrix();
+        // the default apertures are 36mm x 24mm at a focal length of 35mm
+        assert_near(projection[0][0], 70.0 / 36.0);
+        assert_near(projection[1][1], 70.0 / 24.0);
+        assert_near(projection[2][0], 0.0);
+        assert_near(projection[2][1], 0.0);
+        assert_near(projection[2][2], -(far + near) / (far - near));
+        assert_near(projection[2][3], -1.0);
+        assert_near(projection[3][2], -2.0 * far * near / (far - near));
+
+        // the squeeze widens the horizontal aperture only
+        let projection = CameraSample {
+            lens_squeeze_ratio: 2.0,
+            ..Default::default()
+        }
+        .projection_matrix();
+        assert_near(projection[0][0], 70.0 / 72.0);
+        assert_near(projection[1][1], 70.0 / 24.0);
+
+        // overscans extend their side of the window
+        let camera = CameraSample {
+            overscan_left: 0.5,
+            overscan_right: 0.5,
+            overscan_top: 1.0,
+            ..Default::default()
+        };
+        let (left, right, bottom, top) = camera.screen_window();
+        assert_near(left, -1.5);
+        assert_near(right, 1.5);
+        assert_near(bottom, -1.0 / 1.5);
+        assert_near(top, 2.0 / 1.5);
+        let projection = camera.projection_matrix();
+        assert_near(projection[0][0], 70.0 / 54.0);
+        assert_near(projection[1][1], 70.0 / 36.0);
+        assert_near(projection[2][1], 1.0 / 3.0);
+
+        // the film offsets shift the window by a fraction of the aperture
+        let camera = CameraSample {
+            horizontal_film_offset: 0.36,
+            vertical_film_offset: -0.24,
+            ..Default::default()
+        };
+        let (left, right, bottom, top) = camera.screen_window();
+        assert_near(left, -0.8);
+        assert_near(right, 1.2);
+        assert_near(bottom, -1.0 / 1.5 - 2.0 / 15.0);
+        assert_near(top, 1.0 / 1.5 - 2.0 / 15.0);
+        let projection = camera.projection_matrix();
+        assert_near(projection[2][0], 0.2);
+        assert_near(projection[2][1], -0.2);
+    }
+}
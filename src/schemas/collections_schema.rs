@@ -0,0 +1,171 @@
+use crate::object_reader::{ObjectHeader, ObjectReader};
+use crate::pod::*;
+use crate::property::*;
+use crate::reader::ArchiveReader;
+use crate::result::*;
+use crate::time_sampling::SampleSelector;
+use crate::Archive;
+pub use std::convert::TryInto;
+
+// Every collection is a string array property holding the full names of its objects.
+#[derive(Debug)]
+pub struct CollectionsSchema {
+    collections: Vec<ArrayPropertyReader>,
+}
+
+impl CollectionsSchema {
+    pub fn new_from_object_reader(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let properties = object
+            .properties()
+            .ok_or(ParsingError::IncompatibleSchema)?;
+        let properties: CompoundPropertyReader = properties
+            .load_sub_property(0, reader, archive)?
+            .try_into()?;
+
+        let mut collections = vec![];
+        for index in 0..properties.sub_property_count() {
+            // properties that are not string arrays are not collections
+            if let PropertyReader::Array(property) =
+                properties.load_sub_property(index, reader, archive)?
+            {
+                if property.header.data_type == STRING_TYPE {
+                    collections.push(property);
+                }
+            }
+        }
+
+        Ok(Self { collections })
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.collections.iter().all(|x| x.is_constant())
+    }
+    pub fn collection_count(&self) -> usize {
+        self.collections.len()
+    }
+    pub fn collection_names(&self) -> Vec<&str> {
+        self.collections.iter().map(|x| x.name()).collect()
+    }
+    pub fn find_collection_index(&self, name: &str) -> Option<usize> {
+        self.collections.iter().position(|x| x.name() == name)
+    }
+    pub fn sample_count(&self, collection_index: usize) -> Result<u32> {
+        Ok(self.collection(collection_index)?.sample_count())
+    }
+
+    pub fn load_collection_sample(
+        &self,
+        collection_index: usize,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<String>> {
+        let pod_array = self
+            .collection(collection_index)?
+            .load_sample(sample, reader)?;
+        if let PodArray::String(array) = pod_array {
+            Ok(array)
+        } else {
+            Err(InternalError::Unreachable.into())
+        }
+    }
+
+    // Looks up the objects of a collection, paths that do not exist in the archive are an error.
+    pub fn resolve_collection_sample(
+        &self,
+        collection_index: usize,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<ObjectHeader>> {
+        self.load_collection_sample(collection_index, sample, reader)?
+            .iter()
+            .map(|path| Ok(archive.load_object(path, reader)?.header))
+            .collect()
+    }
+
+    fn collection(&self, collection_index: usize) -> Result<&ArrayPropertyReader> {
+        Ok(self
+            .collections
+            .get(collection_index)
+            .ok_or(UserError::OutOfBounds)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive_writer::ArchiveWriter;
+    use crate::metadata::MetaData;
+    use crate::object_writer::ObjectWriter;
+    use crate::schemas::tests::{array_property, schema_object};
+    use std::io::Cursor;
+
+    #[test]
+    fn collections_resolve_their_paths() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut properties = vec![];
+        for (name, paths) in [
+            ("lights", vec!["/a", "/a/b"]),
+            ("broken", vec!["/a/missing"]),
+        ] {
+            let paths = paths.into_iter().map(str::to_owned).collect();
+            properties.push(array_property(
+                name,
+                STRING_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::String(paths)],
+                &mut archive,
+            )?);
+        }
+        // only string arrays are collections
+        properties.push(array_property(
+            "ids",
+            I32_TYPE,
+            MetaData::default(),
+            0,
+            &[PodArray::I32(vec![0])],
+            &mut archive,
+        )?);
+
+        let mut a = ObjectWriter::new("a", MetaData::default());
+        a.add_child(ObjectWriter::new("b", MetaData::default()))?;
+        let mut root = ObjectWriter::new_root();
+        root.add_child(a)?;
+        root.add_child(schema_object(
+            "collections",
+            "AbcCollection_Collections_v1",
+            properties,
+        )?)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let object = archive.load_object("/collections", &data)?;
+        let collections = CollectionsSchema::new_from_object_reader(&object, &data, &archive)?;
+
+        assert_eq!(collections.collection_names(), ["lights", "broken"]);
+        let lights = collections.find_collection_index("lights").unwrap();
+        assert_eq!(
+            collections.load_collection_sample(lights, 0, &data)?,
+            ["/a", "/a/b"]
+        );
+        let objects = collections.resolve_collection_sample(lights, 0, &data, &archive)?;
+        let full_names = objects
+            .iter()
+            .map(|x| x.full_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(full_names, ["/a", "/a/b"]);
+
+        let broken = collections.find_collection_index("broken").unwrap();
+        assert!(collections
+            .resolve_collection_sample(broken, 0, &data, &archive)
+            .is_err());
+        assert!(collections.load_collection_sample(2, 0, &data).is_err());
+
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 use super::base_geom_schema::BaseGeomSchema;
+use super::geom_param::GeomParam;
+use super::load_interpolated_positions;
 use crate::object_reader::ObjectReader;
 use crate::pod::*;
 use crate::property::*;
 use crate::reader::ArchiveReader;
 use crate::result::*;
+use crate::time_sampling::SampleSelector;
 use crate::Archive;
 use std::convert::TryFrom;
 pub use std::convert::TryInto;
@@ -14,8 +17,9 @@ pub enum TopologyVariance {
     HeterogeneousTopology,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum CurvePeriodicity {
+    #[default]
     NonPeriodic = 0,
     Periodic = 1,
 }
@@ -32,8 +36,9 @@ impl TryFrom<u8> for CurvePeriodicity {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum CurveType {
+    #[default]
     Cubic = 0,
     Linear = 1,
     VariableOrder = 2,
@@ -52,8 +57,9 @@ impl TryFrom<u8> for CurveType {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum BasisType {
+    #[default]
     None = 0,
     Bezier = 1,
     Bspline = 2,
@@ -87,18 +93,19 @@ pub struct CurvesSchema {
     curve_basis_and_type: ScalarPropertyReader,
 
     position_weights: Option<ArrayPropertyReader>,
-    uv: Option<ArrayPropertyReader>,
-    n: Option<ArrayPropertyReader>,
-    width: Option<ArrayPropertyReader>,
+    uv: Option<GeomParam>,
+    n: Option<GeomParam>,
+    width: Option<GeomParam>,
     velocities: Option<ArrayPropertyReader>,
     orders: Option<ArrayPropertyReader>,
     knots: Option<ArrayPropertyReader>,
+    arb_geometry_parameters: Option<CompoundPropertyReader>,
 }
 
 impl CurvesSchema {
     pub fn new_from_object_reader(
         object: &ObjectReader,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         archive: &Archive,
     ) -> Result<Self> {
         let properties = object
@@ -129,18 +136,10 @@ impl CurvesSchema {
             .load_sub_property_by_name_checked("w", reader, archive, Some(&F32_TYPE))?
             .map(|x| x.try_into())
             .transpose()?;
-        let uv = properties
-            .load_sub_property_by_name_checked("uv", reader, archive, Some(&F32X2_TYPE))?
-            .map(|x| x.try_into())
-            .transpose()?;
-        let n = properties
-            .load_sub_property_by_name_checked("n", reader, archive, Some(&F32X3_TYPE))?
-            .map(|x| x.try_into())
-            .transpose()?;
-        let width = properties
-            .load_sub_property_by_name_checked("width", reader, archive, Some(&F32_TYPE))?
-            .map(|x| x.try_into())
-            .transpose()?;
+        let uv = GeomParam::load_by_name_checked(&properties, "uv", reader, archive, &F32X2_TYPE)?;
+        let n = GeomParam::load_by_name_checked(&properties, "N", reader, archive, &F32X3_TYPE)?;
+        let width =
+            GeomParam::load_by_name_checked(&properties, "width", reader, archive, &F32_TYPE)?;
         let velocities = properties
             .load_sub_property_by_name_checked(".velocities", reader, archive, Some(&F32X3_TYPE))?
             .map(|x| x.try_into())
@@ -153,6 +152,10 @@ impl CurvesSchema {
             .load_sub_property_by_name_checked(".knots", reader, archive, Some(&F32_TYPE))?
             .map(|x| x.try_into())
             .transpose()?;
+        let arb_geometry_parameters = properties
+            .load_sub_property_by_name(".arbGeomParams", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
 
         Ok(Self {
             base_geom,
@@ -166,9 +169,18 @@ impl CurvesSchema {
             velocities,
             orders,
             knots,
+            arb_geometry_parameters,
         })
     }
 
+    pub fn load_arb_geometry_parameters(
+        &self,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<GeomParam>> {
+        GeomParam::load_all_optional(self.arb_geometry_parameters.as_ref(), reader, archive)
+    }
+
     pub fn topology_variance(&self) -> TopologyVariance {
         if self.n_vertices.is_constant() && self.curve_basis_and_type.is_constant() {
             let is_points_constant = self.positions.is_constant()
@@ -210,23 +222,30 @@ impl CurvesSchema {
     pub fn has_knots(&self) -> bool {
         self.knots.is_some()
     }
+    pub fn uv(&self) -> Option<&GeomParam> {
+        self.uv.as_ref()
+    }
+    pub fn normals(&self) -> Option<&GeomParam> {
+        self.n.as_ref()
+    }
+    pub fn widths(&self) -> Option<&GeomParam> {
+        self.width.as_ref()
+    }
 
     pub fn load_bounds_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<BoundingBox> {
-        self.base_geom.load_bounds_sample(sample_index, reader)
+        self.base_geom.load_bounds_sample(sample, reader)
     }
 
     pub fn load_curve_type_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<(CurveType, CurvePeriodicity, BasisType)> {
-        let pod_array = self
-            .curve_basis_and_type
-            .load_sample(sample_index, reader)?;
+        let pod_array = self.curve_basis_and_type.load_sample(sample, reader)?;
         let pod_array = if let PodArray::U8(array) = pod_array {
             array
         } else {
@@ -242,10 +261,10 @@ impl CurvesSchema {
 
     pub fn load_positions_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Vec<[f32; 3]>> {
-        let pod_array = self.positions.load_sample(sample_index, reader)?;
+        let pod_array = self.positions.load_sample(sample, reader)?;
         let pod_array = if let PodArray::F32(array) = pod_array {
             array
         } else {
@@ -255,12 +274,27 @@ impl CurvesSchema {
         Ok(chunk_vector_by_3(pod_array)?)
     }
 
+    // Loads the positions at `time`, blending the samples around it when the topology allows it.
+    pub fn load_interpolated_positions(
+        &self,
+        time: f64,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<[f32; 3]>> {
+        load_interpolated_positions(
+            &self.positions,
+            self.velocities.as_ref(),
+            self.topology_variance(),
+            time,
+            reader,
+        )
+    }
+
     pub fn load_n_vertices_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Vec<i32>> {
-        let pod_array = self.n_vertices.load_sample(sample_index, reader)?;
+        let pod_array = self.n_vertices.load_sample(sample, reader)?;
         if let PodArray::I32(array) = pod_array {
             Ok(array)
         } else {
@@ -270,16 +304,14 @@ impl CurvesSchema {
 
     pub fn load_curve_basis_and_type_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<[u32; 4]> {
-        let pod_array = self
-            .curve_basis_and_type
-            .load_sample(sample_index, reader)?;
-        let pod_array = if let PodArray::U32(array) = pod_array {
-            array
-        } else {
-            return Err(InternalError::Unreachable.into());
+        // Alembic stores the packed values as bytes, 32 bit integers are accepted as well
+        let pod_array = match self.curve_basis_and_type.load_sample(sample, reader)? {
+            PodArray::U8(array) => array.into_iter().map(u32::from).collect(),
+            PodArray::U32(array) => array,
+            _ => return Err(InternalError::Unreachable.into()),
         };
 
         if pod_array.len() != 4 {
@@ -292,13 +324,15 @@ impl CurvesSchema {
         Ok(slice)
     }
 
+    // The uv, normals and widths loaders resolve the indices, the values keep the scope of the
+    // geometry parameter.
     pub fn load_uv_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Option<Vec<[f32; 2]>>> {
         if let Some(uv) = &self.uv {
-            let array = uv.load_sample(sample_index, reader)?;
+            let array = uv.load_expanded_sample(sample, reader)?;
             if let PodArray::F32(array) = array {
                 Ok(Some(chunk_vector_by_2(array)?))
             } else {
@@ -309,13 +343,30 @@ impl CurvesSchema {
         }
     }
 
+    pub fn load_normals_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<[f32; 3]>>> {
+        if let Some(n) = &self.n {
+            let array = n.load_expanded_sample(sample, reader)?;
+            if let PodArray::F32(array) = array {
+                Ok(Some(chunk_vector_by_3(array)?))
+            } else {
+                Err(InternalError::Unreachable.into())
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
     pub fn load_widths_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Option<Vec<f32>>> {
         if let Some(width) = &self.width {
-            let array = width.load_sample(sample_index, reader)?;
+            let array = width.load_expanded_sample(sample, reader)?;
             if let PodArray::F32(array) = array {
                 Ok(Some(array))
             } else {
@@ -328,11 +379,11 @@ impl CurvesSchema {
 
     pub fn load_velocities_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Option<Vec<[f32; 3]>>> {
         if let Some(velocities) = &self.velocities {
-            let array = velocities.load_sample(sample_index, reader)?;
+            let array = velocities.load_sample(sample, reader)?;
             if let PodArray::F32(array) = array {
                 Ok(Some(chunk_vector_by_3(array)?))
             } else {
@@ -345,11 +396,11 @@ impl CurvesSchema {
 
     pub fn load_orders_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Option<Vec<u8>>> {
         if let Some(orders) = &self.orders {
-            let array = orders.load_sample(sample_index, reader)?;
+            let array = orders.load_sample(sample, reader)?;
             if let PodArray::U8(array) = array {
                 Ok(Some(array))
             } else {
@@ -362,11 +413,11 @@ impl CurvesSchema {
 
     pub fn load_knots_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Option<Vec<f32>>> {
         if let Some(knots) = &self.knots {
-            let array = knots.load_sample(sample_index, reader)?;
+            let array = knots.load_sample(sample, reader)?;
             if let PodArray::F32(array) = array {
                 Ok(Some(array))
             } else {
@@ -0,0 +1,415 @@
+use super::{
+    add_optional_sample, geom_param_meta_data, interpretation_meta_data, schema_meta_data,
+    schema_object_meta_data, BasisType, CurvePeriodicity, CurveType, GEOM_BASE_SCHEMA,
+};
+use crate::archive_writer::ArchiveWriter;
+use crate::metadata::MetaData;
+use crate::object_writer::ObjectWriter;
+use crate::pod::*;
+use crate::property::*;
+use crate::result::*;
+use std::io::{Seek, Write};
+
+const CURVES_SCHEMA: &str = "AbcGeom_Curve_v2";
+
+const CURVE_BASIS_AND_TYPE_TYPE: DataType = DataType {
+    pod_type: PodType::U8,
+    extent: 4,
+};
+
+#[derive(Debug, Default)]
+pub struct CurvesSample<'a> {
+    pub positions: &'a [[f32; 3]],
+    pub n_vertices: &'a [i32],
+
+    pub curve_type: CurveType,
+    pub periodicity: CurvePeriodicity,
+    pub basis_type: BasisType,
+
+    pub position_weights: Option<&'a [f32]>,
+    // uvs, normals and widths have a value per vertex, per curve or a single one
+    pub uvs: Option<&'a [[f32; 2]]>,
+    pub normals: Option<&'a [[f32; 3]]>,
+    pub widths: Option<&'a [f32]>,
+    pub velocities: Option<&'a [[f32; 3]]>,
+    pub orders: Option<&'a [u8]>,
+    pub knots: Option<&'a [f32]>,
+}
+
+#[derive(Debug)]
+pub struct CurvesWriter {
+    name: String,
+    time_sampling_index: u32,
+
+    self_bounds: ScalarPropertyWriter,
+    positions: ArrayPropertyWriter,
+    n_vertices: ArrayPropertyWriter,
+    curve_basis_and_type: ScalarPropertyWriter,
+
+    position_weights: Option<ArrayPropertyWriter>,
+    uv: Option<ArrayPropertyWriter>,
+    n: Option<ArrayPropertyWriter>,
+    width: Option<ArrayPropertyWriter>,
+    velocities: Option<ArrayPropertyWriter>,
+    orders: Option<ArrayPropertyWriter>,
+    knots: Option<ArrayPropertyWriter>,
+}
+
+impl CurvesWriter {
+    pub fn new(name: &str, time_sampling_index: u32) -> Self {
+        Self {
+            name: name.to_owned(),
+            time_sampling_index,
+
+            self_bounds: ScalarPropertyWriter::new(
+                ".selfBnds",
+                BOX_TYPE,
+                interpretation_meta_data("box"),
+                time_sampling_index,
+            ),
+            positions: ArrayPropertyWriter::new(
+                "P",
+                F32X3_TYPE,
+                interpretation_meta_data("point"),
+                time_sampling_index,
+            ),
+            n_vertices: ArrayPropertyWriter::new(
+                "nVertices",
+                I32_TYPE,
+                MetaData::default(),
+                time_sampling_index,
+            ),
+            curve_basis_and_type: ScalarPropertyWriter::new(
+                "curveBasisAndType",
+                CURVE_BASIS_AND_TYPE_TYPE,
+                MetaData::default(),
+                time_sampling_index,
+            ),
+
+            position_weights: None,
+            uv: None,
+            n: None,
+            width: None,
+            velocities: None,
+            orders: None,
+            knots: None,
+        }
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.positions.sample_count()
+    }
+
+    pub fn add_sample<W: Write + Seek>(
+        &mut self,
+        sample: &CurvesSample,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<()> {
+        let vertex_count = sample.positions.len();
+        if sample.n_vertices.iter().map(|&x| x as i64).sum::<i64>() != vertex_count as i64
+            || sample
+                .position_weights
+                .is_some_and(|weights| weights.len() != vertex_count)
+            || sample
+                .velocities
+                .is_some_and(|velocities| velocities.len() != vertex_count)
+            || sample
+                .orders
+                .is_some_and(|orders| orders.len() != sample.n_vertices.len())
+        {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        // the scope of a geometry parameter is stored once, every sample has to keep it
+        let curve_count = sample.n_vertices.len();
+        let uv_scope = sample
+            .uvs
+            .map(|x| geometry_scope(x.len(), vertex_count, curve_count))
+            .transpose()?;
+        let n_scope = sample
+            .normals
+            .map(|x| geometry_scope(x.len(), vertex_count, curve_count))
+            .transpose()?;
+        let width_scope = sample
+            .widths
+            .map(|x| geometry_scope(x.len(), vertex_count, curve_count))
+            .transpose()?;
+        for (property, scope) in [
+            (&self.uv, uv_scope),
+            (&self.n, n_scope),
+            (&self.width, width_scope),
+        ] {
+            if let (Some(property), Some(scope)) = (property, scope) {
+                if property
+                    .header
+                    .meta_data
+                    .tokens
+                    .get("geoScope")
+                    .map(String::as_str)
+                    != Some(scope)
+                {
+                    return Err(UserError::InvalidParameter.into());
+                }
+            }
+        }
+
+        let sample_index = self.sample_count();
+        let time_sampling_index = self.time_sampling_index;
+
+        self.self_bounds.add_sample(
+            &BoundingBox::from_points(sample.positions).to_pod_array(),
+            archive,
+        )?;
+        self.positions.add_sample(
+            &PodArray::F32(sample.positions.iter().flatten().copied().collect()),
+            archive,
+        )?;
+        self.n_vertices
+            .add_sample(&PodArray::I32(sample.n_vertices.to_vec()), archive)?;
+        self.curve_basis_and_type.add_sample(
+            &PodArray::U8(vec![
+                sample.curve_type as u8,
+                sample.periodicity as u8,
+                sample.basis_type as u8,
+                sample.basis_type as u8,
+            ]),
+            archive,
+        )?;
+
+        let optional_properties = [
+            (
+                &mut self.position_weights,
+                sample.position_weights.map(|x| PodArray::F32(x.to_vec())),
+                "w",
+                F32_TYPE,
+                MetaData::default(),
+            ),
+            (
+                &mut self.uv,
+                sample
+                    .uvs
+                    .map(|x| PodArray::F32(x.iter().flatten().copied().collect())),
+                "uv",
+                F32X2_TYPE,
+                geom_param_meta_data(uv_scope.unwrap_or("vtx"), "vector"),
+            ),
+            (
+                &mut self.n,
+                sample
+                    .normals
+                    .map(|x| PodArray::F32(x.iter().flatten().copied().collect())),
+                "N",
+                F32X3_TYPE,
+                geom_param_meta_data(n_scope.unwrap_or("vtx"), "normal"),
+            ),
+            (
+                &mut self.width,
+                sample.widths.map(|x| PodArray::F32(x.to_vec())),
+                "width",
+                F32_TYPE,
+                geom_param_meta_data(width_scope.unwrap_or("vtx"), ""),
+            ),
+            (
+                &mut self.velocities,
+                sample
+                    .velocities
+                    .map(|x| PodArray::F32(x.iter().flatten().copied().collect())),
+                ".velocities",
+                F32X3_TYPE,
+                interpretation_meta_data("vector"),
+            ),
+            (
+                &mut self.orders,
+                sample.orders.map(|x| PodArray::U8(x.to_vec())),
+                ".orders",
+                U8_TYPE,
+                MetaData::default(),
+            ),
+            (
+                &mut self.knots,
+                sample.knots.map(|x| PodArray::F32(x.to_vec())),
+                ".knots",
+                F32_TYPE,
+                MetaData::default(),
+            ),
+        ];
+
+        for (property, value, name, data_type, meta_data) in optional_properties {
+            add_optional_sample(
+                property,
+                value,
+                sample_index,
+                || ArrayPropertyWriter::new(name, data_type, meta_data, time_sampling_index),
+                archive,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn into_object_writer(self) -> Result<ObjectWriter> {
+        let mut geom =
+            CompoundPropertyWriter::new(".geom", schema_meta_data(CURVES_SCHEMA, GEOM_BASE_SCHEMA));
+        geom.add_sub_property(self.self_bounds)?;
+        geom.add_sub_property(self.positions)?;
+        geom.add_sub_property(self.n_vertices)?;
+        geom.add_sub_property(self.curve_basis_and_type)?;
+
+        for property in [
+            self.position_weights,
+            self.uv,
+            self.n,
+            self.width,
+            self.velocities,
+            self.orders,
+            self.knots,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            geom.add_sub_property(property)?;
+        }
+
+        let mut object = ObjectWriter::new(
+            &self.name,
+            schema_object_meta_data(CURVES_SCHEMA, GEOM_BASE_SCHEMA, ".geom"),
+        );
+        object.properties_mut().add_sub_property(geom)?;
+        Ok(object)
+    }
+}
+
+// Curve parameters have a value per vertex, per curve or a single one for all curves.
+fn geometry_scope(len: usize, vertex_count: usize, curve_count: usize) -> Result<&'static str> {
+    if len == vertex_count {
+        Ok("vtx")
+    } else if len == curve_count {
+        Ok("uni")
+    } else if len == 1 {
+        Ok("con")
+    } else {
+        Err(UserError::InvalidParameter.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::geom_param::GeometryScope;
+    use crate::schemas::CurvesSchema;
+    use crate::Archive;
+    use std::io::Cursor;
+
+    // Two linear curves with two and three vertices.
+    const POSITIONS: [[f32; 3]; 5] = [
+        [0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [1.0, 0.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [1.0, 2.0, 3.0],
+    ];
+    const N_VERTICES: [i32; 2] = [2, 3];
+
+    fn curves_sample<'a>() -> CurvesSample<'a> {
+        CurvesSample {
+            positions: &POSITIONS,
+            n_vertices: &N_VERTICES,
+            curve_type: CurveType::Linear,
+            periodicity: CurvePeriodicity::Periodic,
+            basis_type: BasisType::Bspline,
+            ..Default::default()
+        }
+    }
+
+    fn write_curves(samples: &[CurvesSample]) -> Result<Vec<u8>> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut curves = CurvesWriter::new("curves", 0);
+        for sample in samples {
+            curves.add_sample(sample, &mut archive)?;
+        }
+
+        let mut root = ObjectWriter::new_root();
+        root.add_child(curves.into_object_writer()?)?;
+        Ok(archive.finish(root)?.into_inner())
+    }
+
+    fn load_curves(data: &Vec<u8>) -> Result<CurvesSchema> {
+        let archive = Archive::new(data)?;
+        let object = archive.load_object("/curves", data)?;
+        CurvesSchema::new_from_object_reader(&object, data, &archive)
+    }
+
+    #[test]
+    fn curves_round_trip() -> Result<()> {
+        let data = write_curves(&[curves_sample()])?;
+        let curves = load_curves(&data)?;
+
+        assert_eq!(curves.load_positions_sample(0, &data)?, POSITIONS);
+        assert_eq!(curves.load_n_vertices_sample(0, &data)?, N_VERTICES);
+        assert_eq!(
+            curves.load_curve_type_sample(0, &data)?,
+            (
+                CurveType::Linear,
+                CurvePeriodicity::Periodic,
+                BasisType::Bspline
+            )
+        );
+        assert_eq!(
+            curves.load_curve_basis_and_type_sample(0, &data)?,
+            [
+                CurveType::Linear as u32,
+                CurvePeriodicity::Periodic as u32,
+                BasisType::Bspline as u32,
+                BasisType::Bspline as u32,
+            ]
+        );
+        let bounds = curves.load_bounds_sample(0, &data)?;
+        assert_eq!(bounds.min, [0.0, 0.0, 0.0]);
+        assert_eq!(bounds.max, [1.0, 2.0, 3.0]);
+        assert!(!curves.has_uv() && !curves.has_n() && !curves.has_width());
+
+        Ok(())
+    }
+
+    #[test]
+    fn geometry_parameters_keep_their_scope() -> Result<()> {
+        let uvs = [[0.5, 0.5]; 5];
+        let normals = [[0.0, 0.0, 1.0], [0.0, 1.0, 0.0]];
+        let widths = [0.1];
+        let data = write_curves(&[CurvesSample {
+            uvs: Some(&uvs),
+            normals: Some(&normals),
+            widths: Some(&widths),
+            ..curves_sample()
+        }])?;
+        let curves = load_curves(&data)?;
+
+        assert_eq!(curves.uv().unwrap().scope(), GeometryScope::Vertex);
+        assert_eq!(curves.load_uv_sample(0, &data)?.unwrap(), uvs);
+        assert_eq!(curves.normals().unwrap().scope(), GeometryScope::Uniform);
+        assert_eq!(curves.load_normals_sample(0, &data)?.unwrap(), normals);
+        assert_eq!(curves.widths().unwrap().scope(), GeometryScope::Constant);
+        assert_eq!(curves.load_widths_sample(0, &data)?.unwrap(), widths);
+
+        // the lengths have to match one of the scopes
+        assert!(write_curves(&[CurvesSample {
+            widths: Some(&[0.1; 3]),
+            ..curves_sample()
+        }])
+        .is_err());
+        // and the scope can not change between samples
+        assert!(write_curves(&[
+            CurvesSample {
+                widths: Some(&widths),
+                ..curves_sample()
+            },
+            CurvesSample {
+                widths: Some(&[0.1; 5]),
+                ..curves_sample()
+            },
+        ])
+        .is_err());
+
+        Ok(())
+    }
+}
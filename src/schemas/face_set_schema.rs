@@ -0,0 +1,248 @@
+use super::base_geom_schema::BaseGeomSchema;
+use super::load_i32_sample;
+use crate::object_reader::{ObjectHeader, ObjectReader};
+use crate::pod::*;
+use crate::property::*;
+use crate::reader::ArchiveReader;
+use crate::result::*;
+use crate::time_sampling::SampleSelector;
+use crate::Archive;
+pub use std::convert::TryInto;
+
+const FACE_SET_SCHEMA: &str = "AbcGeom_FaceSet_v1";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FaceSetExclusivity {
+    #[default]
+    NonExclusive = 0,
+    // Each face belongs to at most one of the exclusive face sets of a mesh.
+    Exclusive = 1,
+}
+
+#[derive(Debug)]
+pub struct FaceSetSchema {
+    base_geom: BaseGeomSchema,
+
+    faces: ArrayPropertyReader,
+    faces_exclusive: Option<ScalarPropertyReader>,
+}
+
+impl FaceSetSchema {
+    pub fn new_from_object_reader(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let properties = object
+            .properties()
+            .ok_or(ParsingError::IncompatibleSchema)?;
+        let properties: CompoundPropertyReader = properties
+            .load_sub_property(0, reader, archive)?
+            .try_into()?;
+
+        let base_geom = BaseGeomSchema::new_from_properties(&properties, reader, archive)?;
+
+        let faces: ArrayPropertyReader = properties
+            .load_sub_property_by_name_checked(".faces", reader, archive, Some(&I32_TYPE))?
+            .ok_or(ParsingError::IncompatibleSchema)?
+            .try_into()?;
+
+        // only written for exclusive face sets
+        let faces_exclusive = properties
+            .load_sub_property_by_name(".facesExclusive", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        Ok(Self {
+            base_geom,
+            faces,
+            faces_exclusive,
+        })
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.faces.is_constant()
+    }
+    pub fn sample_count(&self) -> u32 {
+        self.faces.sample_count()
+    }
+
+    pub fn load_bounds_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<BoundingBox> {
+        self.base_geom.load_bounds_sample(sample, reader)
+    }
+
+    pub fn load_faces_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<i32>> {
+        load_i32_sample(&self.faces, sample, reader)
+    }
+
+    pub fn load_exclusivity(&self, reader: &dyn ArchiveReader) -> Result<FaceSetExclusivity> {
+        let faces_exclusive = if let Some(faces_exclusive) = &self.faces_exclusive {
+            faces_exclusive
+        } else {
+            return Ok(FaceSetExclusivity::NonExclusive);
+        };
+
+        let is_exclusive = match faces_exclusive.load_sample(0, reader)? {
+            PodArray::Boolean(array) => array.first().copied().unwrap_or(false),
+            PodArray::U32(array) => array.first().is_some_and(|&x| x != 0),
+            PodArray::I32(array) => array.first().is_some_and(|&x| x != 0),
+            _ => return Err(ParsingError::IncompatibleSchema.into()),
+        };
+
+        Ok(if is_exclusive {
+            FaceSetExclusivity::Exclusive
+        } else {
+            FaceSetExclusivity::NonExclusive
+        })
+    }
+}
+
+// Face sets are stored as child objects of the mesh they belong to.
+pub(crate) fn find_face_sets(object: &ObjectReader) -> Vec<ObjectHeader> {
+    object
+        .children
+        .iter()
+        .filter(|child| {
+            child.meta_data.tokens.get("schema").map(String::as_str) == Some(FACE_SET_SCHEMA)
+        })
+        .cloned()
+        .collect()
+}
+
+pub(crate) fn load_face_set(
+    object: &ObjectReader,
+    name: &str,
+    reader: &dyn ArchiveReader,
+    archive: &Archive,
+) -> Result<FaceSetSchema> {
+    let index = *object
+        .child_map
+        .get(name)
+        .ok_or(UserError::InvalidParameter)?;
+    let child = object.load_child(
+        index,
+        reader,
+        &archive.indexed_meta_data,
+        &archive.time_samplings,
+    )?;
+    FaceSetSchema::new_from_object_reader(&child, reader, archive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive_writer::ArchiveWriter;
+    use crate::metadata::MetaData;
+    use crate::object_writer::ObjectWriter;
+    use crate::schemas::tests::{array_property, scalar_property, schema_object, TestArchive};
+    use crate::schemas::{PolyMeshSample, PolyMeshSchema, PolyMeshWriter};
+    use std::io::Cursor;
+
+    fn face_set_object(
+        name: &str,
+        faces: Vec<i32>,
+        faces_exclusive: Option<i32>,
+        archive: &mut TestArchive,
+    ) -> Result<ObjectWriter> {
+        let mut properties = vec![
+            scalar_property(
+                ".selfBnds",
+                BOX_TYPE,
+                MetaData::default(),
+                0,
+                &[BoundingBox::from_points(&[[0.0; 3], [1.0; 3]]).to_pod_array()],
+                archive,
+            )?,
+            array_property(
+                ".faces",
+                I32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::I32(faces)],
+                archive,
+            )?,
+        ];
+        if let Some(faces_exclusive) = faces_exclusive {
+            properties.push(scalar_property(
+                ".facesExclusive",
+                I32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::I32(vec![faces_exclusive])],
+                archive,
+            )?);
+        }
+        schema_object(name, FACE_SET_SCHEMA, properties)
+    }
+
+    #[test]
+    fn face_sets_of_meshes() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut mesh = PolyMeshWriter::new("mesh", 0);
+        let sample = PolyMeshSample {
+            positions: &[
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ],
+            face_indices: &[0, 1, 2, 2, 1, 3],
+            face_counts: &[3, 3],
+            ..Default::default()
+        };
+        mesh.add_sample(&sample, &mut archive)?;
+        let mut mesh = mesh.into_object_writer()?;
+        mesh.add_child(face_set_object("first", vec![0], Some(1), &mut archive)?)?;
+        mesh.add_child(face_set_object("both", vec![0, 1], None, &mut archive)?)?;
+        // children that are not face sets are skipped
+        mesh.add_child(ObjectWriter::new("other", MetaData::default()))?;
+        let mut root = ObjectWriter::new_root();
+        root.add_child(mesh)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let root = archive.load_root_object(&data)?;
+        let object = root.load_child(
+            0,
+            &data,
+            &archive.indexed_meta_data,
+            &archive.time_samplings,
+        )?;
+        let mesh = PolyMeshSchema::new_from_object_reader(&object, &data, &archive)?;
+
+        let names = mesh
+            .face_sets()
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["first", "both"]);
+
+        let first = mesh.load_face_set("first", &object, &data, &archive)?;
+        assert_eq!(first.load_faces_sample(0, &data)?, [0]);
+        assert_eq!(
+            first.load_exclusivity(&data)?,
+            FaceSetExclusivity::Exclusive
+        );
+
+        let both = mesh.load_face_set("both", &object, &data, &archive)?;
+        assert_eq!(both.load_faces_sample(0, &data)?, [0, 1]);
+        assert_eq!(
+            both.load_exclusivity(&data)?,
+            FaceSetExclusivity::NonExclusive
+        );
+
+        assert!(mesh
+            .load_face_set("other", &object, &data, &archive)
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,501 @@
+use crate::metadata::MetaData;
+use crate::pod::*;
+use crate::property::*;
+use crate::reader::ArchiveReader;
+use crate::result::*;
+use crate::time_sampling::SampleSelector;
+use crate::Archive;
+pub use std::convert::TryInto;
+
+// Describes how many values a geometry parameter has and what they are attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GeometryScope {
+    // one value for the whole object
+    Constant,
+    // one value per face or curve
+    Uniform,
+    // one value per vertex, interpolated linearly
+    Varying,
+    // one value per vertex, interpolated like the positions
+    Vertex,
+    // one value per face corner
+    FaceVarying,
+    #[default]
+    Unknown,
+}
+
+impl GeometryScope {
+    fn from_token(token: &str) -> Self {
+        match token {
+            "con" => GeometryScope::Constant,
+            "uni" => GeometryScope::Uniform,
+            "var" => GeometryScope::Varying,
+            "vtx" => GeometryScope::Vertex,
+            "fvr" => GeometryScope::FaceVarying,
+            _ => GeometryScope::Unknown,
+        }
+    }
+}
+
+// A geometry parameter is either stored as a plain array, or as a compound holding the unique
+// values in `.vals` and an index per element in `.indices`.
+#[derive(Debug)]
+pub struct GeomParam {
+    name: String,
+    meta_data: MetaData,
+    scope: GeometryScope,
+    interpretation: String,
+
+    values: ArrayPropertyReader,
+    indices: Option<ArrayPropertyReader>,
+}
+
+impl GeomParam {
+    pub fn new_from_property(
+        property: PropertyReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let (name, meta_data, values, indices) = match property {
+            PropertyReader::Array(values) => (
+                values.name().to_owned(),
+                values.header.meta_data.clone(),
+                values,
+                None,
+            ),
+            PropertyReader::Compound(compound) => {
+                let values: ArrayPropertyReader = compound
+                    .load_sub_property_by_name(".vals", reader, archive)?
+                    .ok_or(ParsingError::IncompatibleSchema)?
+                    .try_into()?;
+                let indices: ArrayPropertyReader = compound
+                    .load_sub_property_by_name(".indices", reader, archive)?
+                    .ok_or(ParsingError::IncompatibleSchema)?
+                    .try_into()?;
+                if !matches!(
+                    indices.header.data_type.pod_type,
+                    PodType::U32 | PodType::I32
+                ) || indices.header.data_type.extent != 1
+                {
+                    return Err(ParsingError::IncompatibleSchema.into());
+                }
+
+                (
+                    compound.name().to_owned(),
+                    compound.header.meta_data.clone(),
+                    values,
+                    Some(indices),
+                )
+            }
+            PropertyReader::Scalar(_) => return Err(ParsingError::IncompatibleSchema.into()),
+        };
+
+        let scope = meta_data
+            .tokens
+            .get("geoScope")
+            .map_or(GeometryScope::Unknown, |x| GeometryScope::from_token(x));
+
+        // writers do not agree on whether the interpretation is stored on the compound or on
+        // its values
+        let interpretation = meta_data
+            .tokens
+            .get("interpretation")
+            .or_else(|| values.header.meta_data.tokens.get("interpretation"))
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(Self {
+            name,
+            meta_data,
+            scope,
+            interpretation,
+            values,
+            indices,
+        })
+    }
+
+    // Loads all geometry parameters of a compound like `.arbGeomParams`, properties that can not
+    // be geometry parameters are skipped.
+    pub fn load_all(
+        parameters: &CompoundPropertyReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<Self>> {
+        let mut geom_params = vec![];
+        for index in 0..parameters.sub_property_count() {
+            let property = parameters.load_sub_property(index, reader, archive)?;
+            match &property {
+                PropertyReader::Scalar(_) => continue,
+                PropertyReader::Compound(x) if x.find_sub_property_index(".vals").is_none() => {
+                    continue
+                }
+                _ => {}
+            }
+            geom_params.push(Self::new_from_property(property, reader, archive)?);
+        }
+        Ok(geom_params)
+    }
+
+    // Like `load_all`, schemas without the compound have no geometry parameters.
+    pub fn load_all_optional(
+        parameters: Option<&CompoundPropertyReader>,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<Self>> {
+        parameters.map_or(Ok(vec![]), |parameters| {
+            Self::load_all(parameters, reader, archive)
+        })
+    }
+
+    pub fn load_by_name(
+        parameters: &CompoundPropertyReader,
+        name: &str,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Option<Self>> {
+        parameters
+            .load_sub_property_by_name(name, reader, archive)?
+            .map(|x| Self::new_from_property(x, reader, archive))
+            .transpose()
+    }
+
+    // Like `load_by_name`, but fails when the values do not have the expected data type.
+    pub fn load_by_name_checked(
+        parameters: &CompoundPropertyReader,
+        name: &str,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+        data_type: &DataType,
+    ) -> Result<Option<Self>> {
+        let geom_param = Self::load_by_name(parameters, name, reader, archive)?;
+        if geom_param
+            .as_ref()
+            .is_some_and(|x| x.data_type() != data_type)
+        {
+            return Err(ParsingError::IncompatibleSchema.into());
+        }
+        Ok(geom_param)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn meta_data(&self) -> &MetaData {
+        &self.meta_data
+    }
+    pub fn scope(&self) -> GeometryScope {
+        self.scope
+    }
+    pub fn interpretation(&self) -> &str {
+        &self.interpretation
+    }
+    pub fn data_type(&self) -> &DataType {
+        &self.values.header.data_type
+    }
+    pub fn is_indexed(&self) -> bool {
+        self.indices.is_some()
+    }
+    pub fn is_constant(&self) -> bool {
+        self.values.is_constant()
+            && if let Some(indices) = &self.indices {
+                indices.is_constant()
+            } else {
+                true
+            }
+    }
+    pub fn sample_count(&self) -> u32 {
+        self.values.sample_count()
+    }
+
+    // Loads the values as they are stored, these are the unique values for indexed parameters.
+    pub fn load_values_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<PodArray> {
+        self.values.load_sample(sample, reader)
+    }
+
+    pub fn load_indices_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<u32>>> {
+        let indices = if let Some(indices) = &self.indices {
+            indices
+        } else {
+            return Ok(None);
+        };
+
+        match indices.load_sample(sample, reader)? {
+            PodArray::U32(array) => Ok(Some(array)),
+            PodArray::I32(array) => array
+                .into_iter()
+                .map(|x| {
+                    x.try_into()
+                        .map_err(|_| ParsingError::InvalidAlembicFile.into())
+                })
+                .collect::<Result<_>>()
+                .map(Some),
+            _ => Err(InternalError::Unreachable.into()),
+        }
+    }
+
+    // Loads a value per element, looking up the indices of indexed parameters.
+    pub fn load_expanded_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<PodArray> {
+        let sample = sample.into();
+        let values = self.load_values_sample(sample, reader)?;
+        if let Some(indices) = self.load_indices_sample(sample, reader)? {
+            expand_indexed(values, &indices, self.data_type().extent as usize)
+        } else {
+            Ok(values)
+        }
+    }
+}
+
+// Expands the values of a mesh geometry parameter to one value per face vertex. Only the mesh
+// topology needed by the scope is loaded.
+pub(crate) fn expand_to_face_varying<T: Copy>(
+    values: Vec<T>,
+    scope: GeometryScope,
+    load_face_counts: impl FnOnce() -> Result<Vec<i32>>,
+    load_face_indices: impl FnOnce() -> Result<Vec<i32>>,
+) -> Result<Vec<T>> {
+    let value = |index: usize| -> Result<T> {
+        Ok(*values.get(index).ok_or(ParsingError::InvalidAlembicFile)?)
+    };
+
+    match scope {
+        GeometryScope::Vertex | GeometryScope::Varying => load_face_indices()?
+            .into_iter()
+            .map(|index| value(index as usize))
+            .collect(),
+        GeometryScope::Uniform => {
+            let mut expanded = vec![];
+            for (face, &count) in load_face_counts()?.iter().enumerate() {
+                let face_value = value(face)?;
+                expanded.resize(expanded.len() + count.max(0) as usize, face_value);
+            }
+            Ok(expanded)
+        }
+        GeometryScope::Constant => {
+            let face_vertex_count = load_face_indices()?.len();
+            Ok(vec![value(0)?; face_vertex_count])
+        }
+        // values without a known scope are assumed to already be per face vertex
+        GeometryScope::FaceVarying | GeometryScope::Unknown => Ok(values),
+    }
+}
+
+// Every index refers to `extent` consecutive values.
+fn expand<T: Clone>(values: Vec<T>, indices: &[u32], extent: usize) -> Result<Vec<T>> {
+    let mut expanded = Vec::with_capacity(indices.len() * extent);
+    for &index in indices {
+        let start = index as usize * extent;
+        let value = values
+            .get(start..start + extent)
+            .ok_or(ParsingError::InvalidAlembicFile)?;
+        expanded.extend_from_slice(value);
+    }
+    Ok(expanded)
+}
+
+pub(crate) fn expand_indexed(values: PodArray, indices: &[u32], extent: usize) -> Result<PodArray> {
+    let extent = extent.max(1);
+    Ok(match values {
+        PodArray::Boolean(array) => PodArray::Boolean(expand(array, indices, extent)?),
+        PodArray::U8(array) => PodArray::U8(expand(array, indices, extent)?),
+        PodArray::I8(array) => PodArray::I8(expand(array, indices, extent)?),
+        PodArray::U16(array) => PodArray::U16(expand(array, indices, extent)?),
+        PodArray::I16(array) => PodArray::I16(expand(array, indices, extent)?),
+        PodArray::U32(array) => PodArray::U32(expand(array, indices, extent)?),
+        PodArray::I32(array) => PodArray::I32(expand(array, indices, extent)?),
+        PodArray::U64(array) => PodArray::U64(expand(array, indices, extent)?),
+        PodArray::I64(array) => PodArray::I64(expand(array, indices, extent)?),
+        PodArray::F16(array) => PodArray::F16(expand(array, indices, extent)?),
+        PodArray::F32(array) => PodArray::F32(expand(array, indices, extent)?),
+        PodArray::F64(array) => PodArray::F64(expand(array, indices, extent)?),
+        PodArray::String(array) => PodArray::String(expand(array, indices, extent)?),
+        PodArray::WString(array) => PodArray::WString(expand(array, indices, extent)?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive_writer::ArchiveWriter;
+    use crate::object_writer::ObjectWriter;
+    use crate::schemas::geom_param_meta_data;
+    use crate::schemas::tests::{array_property, scalar_property};
+    use std::io::Cursor;
+
+    // A quad and a triangle sharing the vertices 1 and 2.
+    const FACE_COUNTS: [i32; 2] = [4, 3];
+    const FACE_INDICES: [i32; 7] = [0, 1, 2, 3, 1, 4, 2];
+
+    fn expand_mesh_values(values: Vec<u32>, scope: GeometryScope) -> Result<Vec<u32>> {
+        expand_to_face_varying(
+            values,
+            scope,
+            || Ok(FACE_COUNTS.to_vec()),
+            || Ok(FACE_INDICES.to_vec()),
+        )
+    }
+
+    #[test]
+    fn values_are_expanded_to_face_varying() -> Result<()> {
+        assert_eq!(
+            expand_mesh_values(vec![7], GeometryScope::Constant)?,
+            [7; 7]
+        );
+        assert_eq!(
+            expand_mesh_values(vec![10, 20], GeometryScope::Uniform)?,
+            [10, 10, 10, 10, 20, 20, 20]
+        );
+        for scope in [GeometryScope::Varying, GeometryScope::Vertex] {
+            assert_eq!(
+                expand_mesh_values(vec![10, 11, 12, 13, 14], scope)?,
+                [10, 11, 12, 13, 11, 14, 12]
+            );
+        }
+        let face_varying = (0..7).collect::<Vec<_>>();
+        assert_eq!(
+            expand_mesh_values(face_varying.clone(), GeometryScope::FaceVarying)?,
+            face_varying
+        );
+
+        // face varying values do not need the topology
+        let no_topology = || Err(ParsingError::IncompatibleSchema.into());
+        assert_eq!(
+            expand_to_face_varying(
+                face_varying.clone(),
+                GeometryScope::FaceVarying,
+                no_topology,
+                no_topology
+            )?,
+            face_varying
+        );
+
+        // too few values for the topology
+        for scope in [
+            GeometryScope::Constant,
+            GeometryScope::Uniform,
+            GeometryScope::Vertex,
+        ] {
+            assert!(matches!(
+                expand_mesh_values(vec![], scope),
+                Err(OgawaError::ParsingError(ParsingError::InvalidAlembicFile))
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn indexed_values_are_expanded() -> Result<()> {
+        let values = PodArray::F32(vec![0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(
+            expand_indexed(values.clone(), &[1, 0, 1], 2)?,
+            PodArray::F32(vec![2.0, 3.0, 0.0, 1.0, 2.0, 3.0])
+        );
+        assert_eq!(
+            expand_indexed(values.clone(), &[3, 3], 1)?,
+            PodArray::F32(vec![3.0, 3.0])
+        );
+        assert!(matches!(
+            expand_indexed(values, &[0, 2], 2),
+            Err(OgawaError::ParsingError(ParsingError::InvalidAlembicFile))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn geometry_parameters_are_loaded() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut object = ObjectWriter::new("params", MetaData::default());
+        let properties = object.properties_mut();
+
+        properties.add_sub_property(array_property(
+            "flat",
+            F32X2_TYPE,
+            geom_param_meta_data("vtx", "vector"),
+            0,
+            &[PodArray::F32(vec![0.0, 1.0, 2.0, 3.0])],
+            &mut archive,
+        )?)?;
+        for (name, indices) in [("indexed", vec![1, 1, 0]), ("out_of_range", vec![0, 2])] {
+            let mut compound =
+                CompoundPropertyWriter::new(name, geom_param_meta_data("fvr", "normal"));
+            compound.add_sub_property(array_property(
+                ".vals",
+                F32X3_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::F32(vec![0.0, 0.0, 1.0, 0.0, 1.0, 0.0])],
+                &mut archive,
+            )?)?;
+            compound.add_sub_property(array_property(
+                ".indices",
+                U32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::U32(indices)],
+                &mut archive,
+            )?)?;
+            properties.add_sub_property(compound)?;
+        }
+        // scalar properties are never geometry parameters
+        properties.add_sub_property(scalar_property(
+            "scalar",
+            F32_TYPE,
+            MetaData::default(),
+            0,
+            &[PodArray::F32(vec![1.0])],
+            &mut archive,
+        )?)?;
+
+        let mut root = ObjectWriter::new_root();
+        root.add_child(object)?;
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let object = archive.load_object("/params", &data)?;
+
+        assert!(GeomParam::load_all_optional(None, &data, &archive)?.is_empty());
+        let geom_params = GeomParam::load_all_optional(object.properties(), &data, &archive)?;
+        let names = geom_params.iter().map(|x| x.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["flat", "indexed", "out_of_range"]);
+
+        let flat = &geom_params[0];
+        assert!(!flat.is_indexed());
+        assert_eq!(flat.scope(), GeometryScope::Vertex);
+        assert_eq!(flat.interpretation(), "vector");
+        assert_eq!(flat.load_indices_sample(0, &data)?, None);
+        assert_eq!(
+            flat.load_expanded_sample(0, &data)?,
+            PodArray::F32(vec![0.0, 1.0, 2.0, 3.0])
+        );
+
+        let indexed = &geom_params[1];
+        assert!(indexed.is_indexed());
+        assert_eq!(indexed.scope(), GeometryScope::FaceVarying);
+        assert_eq!(indexed.interpretation(), "normal");
+        assert_eq!(indexed.data_type(), &F32X3_TYPE);
+        assert_eq!(indexed.load_indices_sample(0, &data)?, Some(vec![1, 1, 0]));
+        assert_eq!(
+            indexed.load_expanded_sample(0, &data)?,
+            PodArray::F32(vec![0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0])
+        );
+
+        assert!(matches!(
+            geom_params[2].load_expanded_sample(0, &data),
+            Err(OgawaError::ParsingError(ParsingError::InvalidAlembicFile))
+        ));
+
+        Ok(())
+    }
+}
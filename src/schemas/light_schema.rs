@@ -0,0 +1,120 @@
+use super::camera_schema::{CameraSample, CameraSchema};
+use super::geom_param::GeomParam;
+use crate::object_reader::ObjectReader;
+use crate::pod::*;
+use crate::property::*;
+use crate::reader::ArchiveReader;
+use crate::result::*;
+use crate::time_sampling::SampleSelector;
+use crate::Archive;
+pub use std::convert::TryInto;
+
+#[derive(Debug)]
+pub struct LightSchema {
+    // lights can describe their projection with an embedded camera schema
+    pub camera: Option<CameraSchema>,
+    pub child_bounds: Option<ScalarPropertyReader>,
+    pub arb_geometry_parameters: Option<CompoundPropertyReader>,
+    pub user_properties: Option<CompoundPropertyReader>,
+}
+
+impl LightSchema {
+    pub fn new_from_object_reader(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let properties = object
+            .properties()
+            .ok_or(ParsingError::IncompatibleSchema)?;
+        let properties: CompoundPropertyReader = properties
+            .load_sub_property(0, reader, archive)?
+            .try_into()?;
+
+        let camera = properties
+            .load_sub_property_by_name(".camera", reader, archive)?
+            .map(|x| {
+                let camera: CompoundPropertyReader = x.try_into()?;
+                CameraSchema::new_from_properties(&camera, reader, archive)
+            })
+            .transpose()?;
+
+        let child_bounds = properties
+            .load_sub_property_by_name_checked(".childBnds", reader, archive, Some(&BOX_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        let arb_geometry_parameters = properties
+            .load_sub_property_by_name(".arbGeomParams", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        let user_properties = properties
+            .load_sub_property_by_name(".userProperties", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        Ok(Self {
+            camera,
+            child_bounds,
+            arb_geometry_parameters,
+            user_properties,
+        })
+    }
+
+    pub fn load_arb_geometry_parameters(
+        &self,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<GeomParam>> {
+        GeomParam::load_all_optional(self.arb_geometry_parameters.as_ref(), reader, archive)
+    }
+
+    pub fn has_camera(&self) -> bool {
+        self.camera.is_some()
+    }
+    pub fn is_constant(&self) -> bool {
+        let is_camera_constant = if let Some(camera) = &self.camera {
+            camera.is_constant()
+        } else {
+            true
+        };
+        let is_child_bounds_constant = if let Some(child_bounds) = &self.child_bounds {
+            child_bounds.is_constant()
+        } else {
+            true
+        };
+        is_camera_constant && is_child_bounds_constant
+    }
+    pub fn sample_count(&self) -> u32 {
+        let camera_sample_count = self.camera.as_ref().map_or(0, |x| x.sample_count());
+        let child_bounds_sample_count = self.child_bounds.as_ref().map_or(0, |x| x.sample_count());
+        camera_sample_count.max(child_bounds_sample_count)
+    }
+
+    pub fn load_camera_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<CameraSample>> {
+        self.camera
+            .as_ref()
+            .map(|camera| camera.load_sample(sample, reader))
+            .transpose()
+    }
+
+    pub fn load_child_bounds_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<BoundingBox>> {
+        let child_bounds = if let Some(child_bounds) = &self.child_bounds {
+            child_bounds
+        } else {
+            return Ok(None);
+        };
+
+        let pod_array = child_bounds.load_sample(sample, reader)?;
+        BoundingBox::from_pod_array(pod_array).map(Some)
+    }
+}
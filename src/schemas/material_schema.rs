@@ -0,0 +1,396 @@
+use crate::object_reader::ObjectReader;
+use crate::pod::*;
+use crate::property::*;
+use crate::reader::ArchiveReader;
+use crate::result::*;
+use crate::time_sampling::SampleSelector;
+use crate::Archive;
+pub use std::convert::TryInto;
+
+const MATERIAL_ASSIGNMENT: &str = ".material.assign";
+
+// A node of a shading network, `connections` map its inputs to the outputs of other nodes.
+#[derive(Debug, Clone)]
+pub struct MaterialNode {
+    pub name: String,
+    pub target: String,
+    pub node_type: String,
+    pub connections: Vec<MaterialConnection>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MaterialConnection {
+    pub input_name: String,
+    pub node_name: String,
+    pub output_name: String,
+}
+
+// The node of a network that provides the shader of a target and shader type.
+#[derive(Debug, Clone)]
+pub struct MaterialTerminal {
+    pub target: String,
+    pub shader_type: String,
+    pub node_name: String,
+    pub output_name: String,
+}
+
+// Exposes a parameter of a network node as a parameter of the whole material.
+#[derive(Debug, Clone)]
+pub struct MaterialInterfaceMapping {
+    pub interface_name: String,
+    pub node_name: String,
+    pub parameter_name: String,
+}
+
+#[derive(Debug)]
+pub struct MaterialSchema {
+    properties: CompoundPropertyReader,
+
+    // (target, shader type, shader name)
+    shaders: Vec<(String, String, String)>,
+    nodes: Vec<MaterialNode>,
+    terminals: Vec<MaterialTerminal>,
+    interface: Vec<MaterialInterfaceMapping>,
+}
+
+impl MaterialSchema {
+    pub fn new_from_object_reader(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let properties = object
+            .properties()
+            .ok_or(ParsingError::IncompatibleSchema)?;
+        let properties: CompoundPropertyReader = properties
+            .load_sub_property_by_name(".material", reader, archive)?
+            .ok_or(ParsingError::IncompatibleSchema)?
+            .try_into()?;
+        Self::new_from_properties(properties, reader, archive)
+    }
+
+    pub fn new_from_properties(
+        properties: CompoundPropertyReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let shaders = load_string_pairs(&properties, ".shaderNames", reader, archive)?
+            .into_iter()
+            .map(|(target_and_type, shader_name)| {
+                let (target, shader_type) = split_target_name(&target_and_type)?;
+                Ok((target, shader_type, shader_name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let terminals = load_string_pairs(&properties, ".terminals", reader, archive)?
+            .into_iter()
+            .map(|(target_and_type, node_and_output)| {
+                let (target, shader_type) = split_target_name(&target_and_type)?;
+                let (node_name, output_name) = split_connection(&node_and_output);
+                Ok(MaterialTerminal {
+                    target,
+                    shader_type,
+                    node_name,
+                    output_name,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let interface = load_string_pairs(&properties, ".interface", reader, archive)?
+            .into_iter()
+            .map(|(interface_name, node_and_parameter)| {
+                let (node_name, parameter_name) = split_connection(&node_and_parameter);
+                MaterialInterfaceMapping {
+                    interface_name,
+                    node_name,
+                    parameter_name,
+                }
+            })
+            .collect();
+
+        let mut nodes = vec![];
+        if let Some(node_properties) =
+            properties.load_sub_property_by_name(".nodes", reader, archive)?
+        {
+            let node_properties: CompoundPropertyReader = node_properties.try_into()?;
+            for index in 0..node_properties.sub_property_count() {
+                let node: CompoundPropertyReader = node_properties
+                    .load_sub_property(index, reader, archive)?
+                    .try_into()?;
+
+                let connections = load_string_pairs(&node, ".connections", reader, archive)?
+                    .into_iter()
+                    .map(|(input_name, node_and_output)| {
+                        let (node_name, output_name) = split_connection(&node_and_output);
+                        MaterialConnection {
+                            input_name,
+                            node_name,
+                            output_name,
+                        }
+                    })
+                    .collect();
+
+                nodes.push(MaterialNode {
+                    name: node.name().to_owned(),
+                    target: load_string(&node, ".target", reader, archive)?.unwrap_or_default(),
+                    node_type: load_string(&node, ".type", reader, archive)?.unwrap_or_default(),
+                    connections,
+                });
+            }
+        }
+
+        Ok(Self {
+            properties,
+            shaders,
+            nodes,
+            terminals,
+            interface,
+        })
+    }
+
+    // Returns the path of the material assigned to `object`, if any.
+    pub fn load_assignment(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Option<String>> {
+        let properties = if let Some(properties) = object.properties() {
+            properties
+        } else {
+            return Ok(None);
+        };
+
+        // the assignment is either stored on the object or inside its schema
+        let mut assignment = load_string(properties, MATERIAL_ASSIGNMENT, reader, archive)?;
+        if assignment.is_none() && properties.sub_property_count() > 0 {
+            if let PropertyReader::Compound(schema) =
+                properties.load_sub_property(0, reader, archive)?
+            {
+                assignment = load_string(&schema, MATERIAL_ASSIGNMENT, reader, archive)?;
+            }
+        }
+
+        Ok(assignment)
+    }
+
+    // Resolves the material of `object`, either through its assignment or from a material that
+    // is stored on the object itself.
+    pub fn load_assigned(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Option<Self>> {
+        if let Some(path) = Self::load_assignment(object, reader, archive)? {
+            let material = archive.load_object(&path, reader)?;
+            return Self::new_from_object_reader(&material, reader, archive).map(Some);
+        }
+
+        let properties = if let Some(properties) = object.properties() {
+            properties
+        } else {
+            return Ok(None);
+        };
+
+        properties
+            .load_sub_property_by_name(".material", reader, archive)?
+            .map(|x| Self::new_from_properties(x.try_into()?, reader, archive))
+            .transpose()
+    }
+
+    pub fn target_names(&self) -> Vec<&str> {
+        let mut target_names = vec![];
+        let targets = self
+            .shaders
+            .iter()
+            .map(|(target, _, _)| target)
+            .chain(self.terminals.iter().map(|terminal| &terminal.target));
+        for target in targets {
+            if !target_names.contains(&target.as_str()) {
+                target_names.push(target.as_str());
+            }
+        }
+        target_names
+    }
+
+    pub fn shader_type_names(&self, target: &str) -> Vec<&str> {
+        self.shaders
+            .iter()
+            .filter(|(shader_target, _, _)| shader_target == target)
+            .map(|(_, shader_type, _)| shader_type.as_str())
+            .collect()
+    }
+
+    pub fn shader(&self, target: &str, shader_type: &str) -> Option<&str> {
+        self.shaders
+            .iter()
+            .find(|(shader_target, shader_shader_type, _)| {
+                shader_target == target && shader_shader_type == shader_type
+            })
+            .map(|(_, _, shader_name)| shader_name.as_str())
+    }
+
+    pub fn load_shader_parameters(
+        &self,
+        target: &str,
+        shader_type: &str,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<(String, PodArray)>> {
+        let name = format!("{}.{}.params", target, shader_type);
+        load_parameters(&self.properties, &name, sample, reader, archive)
+    }
+
+    pub fn nodes(&self) -> &[MaterialNode] {
+        &self.nodes
+    }
+
+    pub fn load_node_parameters(
+        &self,
+        node_name: &str,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<(String, PodArray)>> {
+        let nodes: CompoundPropertyReader = self
+            .properties
+            .load_sub_property_by_name(".nodes", reader, archive)?
+            .ok_or(UserError::InvalidParameter)?
+            .try_into()?;
+        let node: CompoundPropertyReader = nodes
+            .load_sub_property_by_name(node_name, reader, archive)?
+            .ok_or(UserError::InvalidParameter)?
+            .try_into()?;
+        load_parameters(&node, ".params", sample, reader, archive)
+    }
+
+    pub fn terminals(&self) -> &[MaterialTerminal] {
+        &self.terminals
+    }
+
+    pub fn interface(&self) -> &[MaterialInterfaceMapping] {
+        &self.interface
+    }
+
+    pub fn load_interface_parameters(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<(String, PodArray)>> {
+        load_parameters(
+            &self.properties,
+            ".interfaceParams",
+            sample,
+            reader,
+            archive,
+        )
+    }
+}
+
+// Shader targets and types are joined with a dot, like "arnold.surface".
+fn split_target_name(name: &str) -> Result<(String, String)> {
+    let (target, shader_type) = name
+        .split_once('.')
+        .ok_or(ParsingError::InvalidAlembicFile)?;
+    Ok((target.to_owned(), shader_type.to_owned()))
+}
+
+// Connections name a node and optionally one of its outputs, like "noise.outColor".
+fn split_connection(connection: &str) -> (String, String) {
+    match connection.split_once('.') {
+        Some((node_name, output_name)) => (node_name.to_owned(), output_name.to_owned()),
+        None => (connection.to_owned(), String::new()),
+    }
+}
+
+fn load_strings(
+    properties: &CompoundPropertyReader,
+    name: &str,
+    reader: &dyn ArchiveReader,
+    archive: &Archive,
+) -> Result<Option<Vec<String>>> {
+    let pod_array = match properties.load_sub_property_by_name(name, reader, archive)? {
+        Some(PropertyReader::Scalar(property)) if property.sample_count() > 0 => {
+            property.load_sample(0, reader)?
+        }
+        Some(PropertyReader::Array(property)) if property.sample_count() > 0 => {
+            property.load_sample(0, reader)?
+        }
+        None => return Ok(None),
+        _ => return Err(ParsingError::IncompatibleSchema.into()),
+    };
+
+    if let PodArray::String(array) = pod_array {
+        Ok(Some(array))
+    } else {
+        Err(ParsingError::IncompatibleSchema.into())
+    }
+}
+
+fn load_string(
+    properties: &CompoundPropertyReader,
+    name: &str,
+    reader: &dyn ArchiveReader,
+    archive: &Archive,
+) -> Result<Option<String>> {
+    Ok(load_strings(properties, name, reader, archive)?.and_then(|x| x.into_iter().next()))
+}
+
+fn load_string_pairs(
+    properties: &CompoundPropertyReader,
+    name: &str,
+    reader: &dyn ArchiveReader,
+    archive: &Archive,
+) -> Result<Vec<(String, String)>> {
+    let strings = load_strings(properties, name, reader, archive)?.unwrap_or_default();
+    Ok(strings
+        .chunks_exact(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect())
+}
+
+// Parameters can be of any type, so they are returned as they are stored.
+fn load_parameters(
+    properties: &CompoundPropertyReader,
+    name: &str,
+    sample: impl Into<SampleSelector>,
+    reader: &dyn ArchiveReader,
+    archive: &Archive,
+) -> Result<Vec<(String, PodArray)>> {
+    let sample = sample.into();
+    let parameters: CompoundPropertyReader =
+        match properties.load_sub_property_by_name(name, reader, archive)? {
+            Some(parameters) => parameters.try_into()?,
+            None => return Ok(vec![]),
+        };
+
+    let mut values = vec![];
+    for index in 0..parameters.sub_property_count() {
+        // parameters are usually constant, so their last sample is used past the end
+        let value = match parameters.load_sub_property(index, reader, archive)? {
+            PropertyReader::Scalar(property) if property.sample_count() > 0 => (
+                property.name().to_owned(),
+                property.load_sample(
+                    property
+                        .sample_index(sample)
+                        .min(property.sample_count() - 1),
+                    reader,
+                )?,
+            ),
+            PropertyReader::Array(property) if property.sample_count() > 0 => (
+                property.name().to_owned(),
+                property.load_sample(
+                    property
+                        .sample_index(sample)
+                        .min(property.sample_count() - 1),
+                    reader,
+                )?,
+            ),
+            _ => continue,
+        };
+        values.push(value);
+    }
+
+    Ok(values)
+}
@@ -1,19 +1,54 @@
 mod base_geom_schema;
+mod camera_schema;
+mod collections_schema;
 mod curves_schema;
+mod curves_writer;
+mod face_set_schema;
+mod geom_param;
+mod light_schema;
+mod material_schema;
+mod nupatch_schema;
+mod points_schema;
 mod polymesh_schema;
+mod polymesh_writer;
+mod subd_schema;
+mod xform_op;
 mod xform_schema;
+mod xform_writer;
 
 pub use base_geom_schema::BaseGeomSchema;
+pub use camera_schema::{CameraSample, CameraSchema};
+pub use collections_schema::CollectionsSchema;
 pub use curves_schema::{BasisType, CurvePeriodicity, CurveType, CurvesSchema, TopologyVariance};
+pub use curves_writer::{CurvesSample, CurvesWriter};
+pub use face_set_schema::{FaceSetExclusivity, FaceSetSchema};
+pub use geom_param::{GeomParam, GeometryScope};
+pub use light_schema::LightSchema;
+pub use material_schema::{
+    MaterialConnection, MaterialInterfaceMapping, MaterialNode, MaterialSchema, MaterialTerminal,
+};
+pub use nupatch_schema::{NuPatchSample, NuPatchSchema, NuPatchTrimCurve};
+pub use points_schema::PointsSchema;
 pub use polymesh_schema::PolyMeshSchema;
+pub use polymesh_writer::{PolyMeshSample, PolyMeshWriter};
+pub use subd_schema::SubDSchema;
+pub use xform_op::{XformOp, XformOperationType};
 pub use xform_schema::XformSchema;
+pub use xform_writer::{XformSample, XformWriter};
 
+pub(crate) use xform_schema::{load_hierarchy_bounds, load_world_matrix};
+
+use crate::archive_writer::ArchiveWriter;
+use crate::metadata::MetaData;
 use crate::object_reader::ObjectReader;
+use crate::pod::{chunk_vector_by_3, BoundingBox, PodArray};
 use crate::reader::ArchiveReader;
 use crate::result::*;
+use crate::time_sampling::{SampleSelector, TimeSampling};
 use crate::Archive;
 
 use crate::property::*;
+use std::io::{Seek, Write};
 
 #[derive(Debug)]
 pub enum Schema {
@@ -21,12 +56,20 @@ pub enum Schema {
     Curves(Box<CurvesSchema>),
     Xform(Box<XformSchema>),
     PolyMesh(Box<PolyMeshSchema>),
+    Points(Box<PointsSchema>),
+    SubD(Box<SubDSchema>),
+    NuPatch(Box<NuPatchSchema>),
+    FaceSet(Box<FaceSetSchema>),
+    Camera(Box<CameraSchema>),
+    Light(Box<LightSchema>),
+    Material(Box<MaterialSchema>),
+    Collections(Box<CollectionsSchema>),
 }
 
 impl Schema {
     pub fn parse(
         object: &ObjectReader,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         archive: &Archive,
     ) -> Result<Schema> {
         assert!(
@@ -67,34 +110,671 @@ impl Schema {
             "AbcGeom_PolyMesh_v1" => Ok(Schema::PolyMesh(Box::new(
                 PolyMeshSchema::new_from_object_reader(object, reader, archive)?,
             ))),
-            "AbcGeom_SubD_v1" => {
-                println!("AbcGeom_SubD_v1 schema not yet implemented.");
-                Err(ParsingError::UnsupportedAlembicFile.into())
-            }
-            "AbcGeom_NuPatch_v2" => {
-                println!("AbcGeom_NuPatch_v2 schema not yet implemented.");
-                Err(ParsingError::UnsupportedAlembicFile.into())
-            }
-            "AbcGeom_FaceSet_v1" => {
-                println!("AbcGeom_FaceSet_v1 schema not yet implemented.");
-                Err(ParsingError::UnsupportedAlembicFile.into())
-            }
-            "AbcGeom_Points_v1" => {
-                println!("AbcGeom_Points_v1 schema not yet implemented.");
-                Err(ParsingError::UnsupportedAlembicFile.into())
-            }
+            "AbcGeom_SubD_v1" => Ok(Schema::SubD(Box::new(SubDSchema::new_from_object_reader(
+                object, reader, archive,
+            )?))),
+            "AbcGeom_NuPatch_v2" => Ok(Schema::NuPatch(Box::new(
+                NuPatchSchema::new_from_object_reader(object, reader, archive)?,
+            ))),
+            "AbcGeom_FaceSet_v1" => Ok(Schema::FaceSet(Box::new(
+                FaceSetSchema::new_from_object_reader(object, reader, archive)?,
+            ))),
+            "AbcGeom_Points_v1" => Ok(Schema::Points(Box::new(
+                PointsSchema::new_from_object_reader(object, reader, archive)?,
+            ))),
             "AbcGeom_Xform_v3" => Ok(Schema::Xform(Box::new(
                 XformSchema::new_from_object_reader(object, reader, archive)?,
             ))),
-            "AbcGeom_Light_v1" => {
-                println!("AbcGeom_Light_v1 schema not yet implemented.");
-                Err(ParsingError::UnsupportedAlembicFile.into())
+            "AbcGeom_Light_v1" => Ok(Schema::Light(Box::new(
+                LightSchema::new_from_object_reader(object, reader, archive)?,
+            ))),
+            "AbcGeom_Camera_v1" => Ok(Schema::Camera(Box::new(
+                CameraSchema::new_from_object_reader(object, reader, archive)?,
+            ))),
+            "AbcMaterial_Material_v1" => Ok(Schema::Material(Box::new(
+                MaterialSchema::new_from_object_reader(object, reader, archive)?,
+            ))),
+            "AbcCollection_Collections_v1" => Ok(Schema::Collections(Box::new(
+                CollectionsSchema::new_from_object_reader(object, reader, archive)?,
+            ))),
+            _ => Err(ParsingError::IncompatibleSchema.into()),
+        }
+    }
+
+    // Geometry returns its own bounds, xforms, cameras and lights the bounds of their children.
+    pub fn load_bounds_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<BoundingBox>> {
+        let sample = sample.into();
+        Ok(match self {
+            Schema::BaseGeom(x) => Some(x.load_bounds_sample(sample, reader)?),
+            Schema::Curves(x) => Some(x.load_bounds_sample(sample, reader)?),
+            Schema::PolyMesh(x) => Some(x.load_bounds_sample(sample, reader)?),
+            Schema::Points(x) => Some(x.load_bounds_sample(sample, reader)?),
+            Schema::SubD(x) => Some(x.load_bounds_sample(sample, reader)?),
+            Schema::NuPatch(x) => Some(x.load_bounds_sample(sample, reader)?),
+            Schema::FaceSet(x) => Some(x.load_bounds_sample(sample, reader)?),
+            Schema::Xform(x) => x.load_child_bounds_sample(sample, reader)?,
+            Schema::Camera(x) => x.load_child_bounds_sample(sample, reader)?,
+            Schema::Light(x) => x.load_child_bounds_sample(sample, reader)?,
+            Schema::Material(_) | Schema::Collections(_) => None,
+        })
+    }
+}
+
+// schema names shared by the readers and the writers
+pub(crate) const XFORM_SCHEMA: &str = "AbcGeom_Xform_v3";
+pub(crate) const GEOM_BASE_SCHEMA: &str = "AbcGeom_GeomBase_v1";
+
+pub(crate) fn schema_meta_data(schema: &str, schema_base_type: &str) -> MetaData {
+    let mut meta_data = MetaData::default();
+    meta_data
+        .tokens
+        .insert("schema".to_owned(), schema.to_owned());
+    if !schema_base_type.is_empty() {
+        meta_data
+            .tokens
+            .insert("schemaBaseType".to_owned(), schema_base_type.to_owned());
+    }
+    meta_data
+}
+
+// The object meta data additionally names the compound property holding the schema.
+pub(crate) fn schema_object_meta_data(
+    schema: &str,
+    schema_base_type: &str,
+    property_name: &str,
+) -> MetaData {
+    let mut meta_data = schema_meta_data(schema, schema_base_type);
+    meta_data.tokens.insert(
+        "schemaObjTitle".to_owned(),
+        format!("{}:{}", schema, property_name),
+    );
+    meta_data
+}
+
+pub(crate) fn interpretation_meta_data(interpretation: &str) -> MetaData {
+    let mut meta_data = MetaData::default();
+    meta_data
+        .tokens
+        .insert("interpretation".to_owned(), interpretation.to_owned());
+    meta_data
+}
+
+pub(crate) fn geom_param_meta_data(geometry_scope: &str, interpretation: &str) -> MetaData {
+    let mut meta_data = if interpretation.is_empty() {
+        MetaData::default()
+    } else {
+        interpretation_meta_data(interpretation)
+    };
+    meta_data
+        .tokens
+        .insert("geoScope".to_owned(), geometry_scope.to_owned());
+    meta_data
+        .tokens
+        .insert("isGeomParam".to_owned(), "true".to_owned());
+    meta_data
+}
+
+// Adds a sample to an optional property, creating the property the first time a value is
+// provided. Samples before its creation and samples without a value are left empty.
+pub(crate) fn add_optional_sample<W: Write + Seek>(
+    property: &mut Option<ArrayPropertyWriter>,
+    sample: Option<PodArray>,
+    sample_index: u32,
+    create: impl FnOnce() -> ArrayPropertyWriter,
+    archive: &mut ArchiveWriter<W>,
+) -> Result<()> {
+    if property.is_none() && sample.is_some() {
+        let mut new_property = create();
+        let empty = PodArray::empty(new_property.header.data_type.pod_type)?;
+        for _ in 0..sample_index {
+            new_property.add_sample(&empty, archive)?;
+        }
+        *property = Some(new_property);
+    }
+
+    if let Some(property) = property {
+        let sample = match sample {
+            Some(sample) => sample,
+            None => PodArray::empty(property.header.data_type.pod_type)?,
+        };
+        property.add_sample(&sample, archive)?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn load_i32_sample(
+    property: &ArrayPropertyReader,
+    sample: impl Into<SampleSelector>,
+    reader: &dyn ArchiveReader,
+) -> Result<Vec<i32>> {
+    let pod_array = property.load_sample(sample, reader)?;
+    if let PodArray::I32(array) = pod_array {
+        Ok(array)
+    } else {
+        Err(InternalError::Unreachable.into())
+    }
+}
+
+pub(crate) fn load_f32_sample(
+    property: &ArrayPropertyReader,
+    sample: impl Into<SampleSelector>,
+    reader: &dyn ArchiveReader,
+) -> Result<Vec<f32>> {
+    let pod_array = property.load_sample(sample, reader)?;
+    if let PodArray::F32(array) = pod_array {
+        Ok(array)
+    } else {
+        Err(InternalError::Unreachable.into())
+    }
+}
+
+pub(crate) fn load_i32_scalar_sample(
+    property: &ScalarPropertyReader,
+    sample: impl Into<SampleSelector>,
+    reader: &dyn ArchiveReader,
+) -> Result<i32> {
+    let pod_array = property.load_sample(sample, reader)?;
+    if let PodArray::I32(array) = pod_array {
+        array
+            .first()
+            .copied()
+            .ok_or_else(|| ParsingError::InvalidAlembicFile.into())
+    } else {
+        Err(InternalError::Unreachable.into())
+    }
+}
+
+// Interpolates the positions between the samples around `time`. Samples can only be blended when
+// they share their topology, otherwise the earlier sample is moved along its velocities.
+pub(crate) fn load_interpolated_positions(
+    positions: &ArrayPropertyReader,
+    velocities: Option<&ArrayPropertyReader>,
+    topology_variance: TopologyVariance,
+    time: f64,
+    reader: &dyn ArchiveReader,
+) -> Result<Vec<[f32; 3]>> {
+    let load = |property: &ArrayPropertyReader, sample_index: u32, reader: &dyn ArchiveReader| {
+        let pod_array = property.load_sample(sample_index, reader)?;
+        if let PodArray::F32(array) = pod_array {
+            Ok(chunk_vector_by_3(array)?)
+        } else {
+            Err(OgawaError::from(InternalError::Unreachable))
+        }
+    };
+
+    let default_time_sampling = TimeSampling::default();
+    let time_sampling = positions
+        .header
+        .time_sampling
+        .as_deref()
+        .unwrap_or(&default_time_sampling);
+    let floor_index = positions.sample_index(SampleSelector::Floor(time));
+    let ceil_index = positions.sample_index(SampleSelector::Ceil(time));
+    let floor_time = time_sampling.sample_time(floor_index);
+    let mut floor_positions = load(positions, floor_index, reader)?;
+
+    if topology_variance == TopologyVariance::HeterogeneousTopology {
+        if let Some(velocities) = velocities {
+            // the velocities can have a time sampling of their own
+            let velocities_index = velocities.sample_index(SampleSelector::Floor(time));
+            let velocities = load(velocities, velocities_index, reader)?;
+            if velocities.len() != floor_positions.len() {
+                return Err(ParsingError::InvalidAlembicFile.into());
             }
-            "AbcGeom_Camera_v1" => {
-                println!("AbcGeom_Camera_v1 schema not yet implemented.");
-                Err(ParsingError::UnsupportedAlembicFile.into())
+
+            let delta = (time - floor_time) as f32;
+            for (position, velocity) in floor_positions.iter_mut().zip(velocities) {
+                for axis in 0..3 {
+                    position[axis] += velocity[axis] * delta;
+                }
             }
-            _ => Err(ParsingError::IncompatibleSchema.into()),
         }
+        return Ok(floor_positions);
+    }
+
+    // samplings that do not advance in time can not be blended
+    let ceil_time = time_sampling.sample_time(ceil_index);
+    if floor_index == ceil_index || ceil_time <= floor_time {
+        return Ok(floor_positions);
+    }
+
+    let ceil_positions = load(positions, ceil_index, reader)?;
+    if ceil_positions.len() != floor_positions.len() {
+        return Err(ParsingError::InvalidAlembicFile.into());
+    }
+
+    let alpha = ((time - floor_time) / (ceil_time - floor_time)) as f32;
+    for (position, ceil_position) in floor_positions.iter_mut().zip(ceil_positions) {
+        for axis in 0..3 {
+            position[axis] += (ceil_position[axis] - position[axis]) * alpha;
+        }
+    }
+    Ok(floor_positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pod::*;
+    use crate::ObjectWriter;
+    use std::io::Cursor;
+
+    pub(crate) type TestArchive = ArchiveWriter<Cursor<Vec<u8>>>;
+
+    // Wraps the properties of a schema into an object, like the schema writers do.
+    pub(crate) fn schema_object(
+        name: &str,
+        schema: &str,
+        properties: Vec<PropertyWriter>,
+    ) -> Result<ObjectWriter> {
+        let mut compound =
+            CompoundPropertyWriter::new(".geom", schema_meta_data(schema, GEOM_BASE_SCHEMA));
+        for property in properties {
+            compound.add_sub_property(property)?;
+        }
+
+        let mut object = ObjectWriter::new(
+            name,
+            schema_object_meta_data(schema, GEOM_BASE_SCHEMA, ".geom"),
+        );
+        object.properties_mut().add_sub_property(compound)?;
+        Ok(object)
+    }
+
+    pub(crate) fn array_property(
+        name: &str,
+        data_type: DataType,
+        meta_data: MetaData,
+        time_sampling_index: u32,
+        samples: &[PodArray],
+        archive: &mut TestArchive,
+    ) -> Result<PropertyWriter> {
+        let mut property =
+            ArrayPropertyWriter::new(name, data_type, meta_data, time_sampling_index);
+        for sample in samples {
+            property.add_sample(sample, archive)?;
+        }
+        Ok(property.into())
+    }
+
+    pub(crate) fn scalar_property(
+        name: &str,
+        data_type: DataType,
+        meta_data: MetaData,
+        time_sampling_index: u32,
+        samples: &[PodArray],
+        archive: &mut TestArchive,
+    ) -> Result<PropertyWriter> {
+        let mut property =
+            ScalarPropertyWriter::new(name, data_type, meta_data, time_sampling_index);
+        for sample in samples {
+            property.add_sample(sample, archive)?;
+        }
+        Ok(property.into())
+    }
+
+    fn write_meshes(samples: &[(&str, Vec<PolyMeshSample>)]) -> Result<Vec<u8>> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut root = ObjectWriter::new_root();
+        for (name, samples) in samples {
+            let mut mesh = PolyMeshWriter::new(name, 0);
+            for sample in samples {
+                mesh.add_sample(sample, &mut archive)?;
+            }
+            root.add_child(mesh.into_object_writer()?)?;
+        }
+        Ok(archive.finish(root)?.into_inner())
+    }
+
+    #[test]
+    fn interpolated_positions() -> Result<()> {
+        let triangle = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let moved_triangle = triangle.map(|[x, y, z]| [x + 2.0, y + 4.0, z]);
+        let quad = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let triangle_velocities = [[1.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 4.0]];
+        let quad_velocities = [[0.0; 3]; 4];
+
+        let triangle_sample = |positions, velocities| PolyMeshSample {
+            positions,
+            face_indices: &[0, 1, 2],
+            face_counts: &[3],
+            velocities,
+            ..Default::default()
+        };
+        let data = write_meshes(&[
+            (
+                "homogeneous",
+                vec![
+                    triangle_sample(&triangle, None),
+                    triangle_sample(&moved_triangle, None),
+                ],
+            ),
+            (
+                "heterogeneous",
+                vec![
+                    triangle_sample(&triangle, Some(&triangle_velocities)),
+                    PolyMeshSample {
+                        positions: &quad,
+                        face_indices: &[0, 1, 2, 3],
+                        face_counts: &[4],
+                        velocities: Some(&quad_velocities),
+                        ..Default::default()
+                    },
+                ],
+            ),
+        ])?;
+        let archive = Archive::new(&data)?;
+
+        // the samples are at time 0 and 1
+        let object = archive.load_object("/homogeneous", &data)?;
+        let mesh = PolyMeshSchema::new_from_object_reader(&object, &data, &archive)?;
+        assert_eq!(
+            mesh.topology_variance(),
+            TopologyVariance::HomogeneousTopology
+        );
+        assert_eq!(mesh.load_interpolated_vertices(0.0, &data)?, triangle);
+        assert_eq!(mesh.load_interpolated_vertices(1.0, &data)?, moved_triangle);
+        assert_eq!(
+            mesh.load_interpolated_vertices(0.5, &data)?,
+            triangle.map(|[x, y, z]| [x + 1.0, y + 2.0, z])
+        );
+
+        // meshes with changing topology move the earlier sample along its velocities
+        let object = archive.load_object("/heterogeneous", &data)?;
+        let mesh = PolyMeshSchema::new_from_object_reader(&object, &data, &archive)?;
+        assert_eq!(
+            mesh.topology_variance(),
+            TopologyVariance::HeterogeneousTopology
+        );
+        assert_eq!(
+            mesh.load_interpolated_vertices(0.25, &data)?,
+            [[0.25, 0.0, 0.0], [1.0, 0.5, 0.0], [0.0, 1.0, 1.0]]
+        );
+        assert_eq!(mesh.load_interpolated_vertices(1.0, &data)?, quad);
+
+        Ok(())
+    }
+
+    #[test]
+    fn velocities_use_their_own_time_sampling() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let velocity_sampling = archive.add_time_sampling(TimeSampling::uniform(0.5, 0.0));
+
+        // the positions are sampled at 0 and 1, the velocities at 0, 0.5 and 1
+        let f32_samples = |samples: &[Vec<[f32; 3]>]| {
+            samples
+                .iter()
+                .map(|sample| PodArray::F32(sample.iter().flatten().copied().collect()))
+                .collect::<Vec<_>>()
+        };
+        let triangle = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let quad = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let properties = vec![
+            scalar_property(
+                ".selfBnds",
+                BOX_TYPE,
+                MetaData::default(),
+                0,
+                &[BoundingBox::from_points(&quad).to_pod_array()],
+                &mut archive,
+            )?,
+            array_property(
+                "P",
+                F32X3_TYPE,
+                MetaData::default(),
+                0,
+                &f32_samples(&[triangle.to_vec(), quad]),
+                &mut archive,
+            )?,
+            array_property(
+                ".faceIndices",
+                I32_TYPE,
+                MetaData::default(),
+                0,
+                &[
+                    PodArray::I32(vec![0, 1, 2]),
+                    PodArray::I32(vec![0, 1, 2, 3]),
+                ],
+                &mut archive,
+            )?,
+            array_property(
+                ".faceCounts",
+                I32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::I32(vec![3]), PodArray::I32(vec![4])],
+                &mut archive,
+            )?,
+            array_property(
+                ".velocities",
+                F32X3_TYPE,
+                MetaData::default(),
+                velocity_sampling,
+                &f32_samples(&[
+                    vec![[1.0, 0.0, 0.0]; 3],
+                    vec![[0.0, 2.0, 0.0]; 3],
+                    vec![[0.0, 0.0, 1.0]; 2],
+                ]),
+                &mut archive,
+            )?,
+        ];
+        let mut root = ObjectWriter::new_root();
+        root.add_child(schema_object("mesh", "AbcGeom_PolyMesh_v1", properties)?)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let object = archive.load_object("/mesh", &data)?;
+        let mesh = PolyMeshSchema::new_from_object_reader(&object, &data, &archive)?;
+
+        assert_eq!(
+            mesh.load_interpolated_vertices(0.75, &data)?,
+            triangle.map(|[x, y, z]| [x, y + 1.5, z])
+        );
+        // the velocities at time 1 do not match the vertices
+        assert!(mesh.load_interpolated_vertices(1.0, &data).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn samples_at_the_same_time_are_not_blended() -> Result<()> {
+        let triangle = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let moved_triangle = triangle.map(|[x, y, z]| [x + 1.0, y, z]);
+
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        // every sample is at time 1
+        let time_sampling = archive.add_time_sampling(TimeSampling::uniform(0.0, 1.0));
+        let mut mesh = PolyMeshWriter::new("mesh", time_sampling);
+        for positions in [&triangle, &moved_triangle] {
+            let sample = PolyMeshSample {
+                positions,
+                face_indices: &[0, 1, 2],
+                face_counts: &[3],
+                ..Default::default()
+            };
+            mesh.add_sample(&sample, &mut archive)?;
+        }
+        let mut root = ObjectWriter::new_root();
+        root.add_child(mesh.into_object_writer()?)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let object = archive.load_object("/mesh", &data)?;
+        let mesh = PolyMeshSchema::new_from_object_reader(&object, &data, &archive)?;
+        assert_eq!(mesh.load_interpolated_vertices(1.0, &data)?, moved_triangle);
+
+        Ok(())
+    }
+
+    #[test]
+    fn schema_bounds() -> Result<()> {
+        // the geometry has self bounds, the light and the camera child bounds
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let unit_bounds = |offset: f64| BoundingBox {
+            min: [offset; 3],
+            max: [offset + 1.0; 3],
+        };
+        let bounds_property = |name: &str, offset: f64, archive: &mut TestArchive| {
+            scalar_property(
+                name,
+                BOX_TYPE,
+                MetaData::default(),
+                0,
+                &[unit_bounds(offset).to_pod_array()],
+                archive,
+            )
+        };
+        let i32_scalar = |name: &str, value: i32, archive: &mut TestArchive| {
+            scalar_property(
+                name,
+                I32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::I32(vec![value])],
+                archive,
+            )
+        };
+        let array =
+            |name: &str, data_type: DataType, sample: PodArray, archive: &mut TestArchive| {
+                array_property(name, data_type, MetaData::default(), 0, &[sample], archive)
+            };
+        let triangle = || PodArray::F32(vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+        let camera_core = DataType {
+            pod_type: PodType::F64,
+            extent: 16,
+        };
+
+        let objects = [
+            (
+                "points",
+                "AbcGeom_Points_v1",
+                vec![
+                    bounds_property(".selfBnds", 1.0, &mut archive)?,
+                    array("P", F32X3_TYPE, triangle(), &mut archive)?,
+                    array(
+                        ".pointIds",
+                        U64_TYPE,
+                        PodArray::U64(vec![0, 1, 2]),
+                        &mut archive,
+                    )?,
+                ],
+            ),
+            (
+                "subd",
+                "AbcGeom_SubD_v1",
+                vec![
+                    bounds_property(".selfBnds", 2.0, &mut archive)?,
+                    array("P", F32X3_TYPE, triangle(), &mut archive)?,
+                    array(
+                        ".faceIndices",
+                        I32_TYPE,
+                        PodArray::I32(vec![0, 1, 2]),
+                        &mut archive,
+                    )?,
+                    array(
+                        ".faceCounts",
+                        I32_TYPE,
+                        PodArray::I32(vec![3]),
+                        &mut archive,
+                    )?,
+                ],
+            ),
+            (
+                "nupatch",
+                "AbcGeom_NuPatch_v2",
+                vec![
+                    bounds_property(".selfBnds", 3.0, &mut archive)?,
+                    array("P", F32X3_TYPE, PodArray::F32(vec![0.0; 12]), &mut archive)?,
+                    i32_scalar("nu", 2, &mut archive)?,
+                    i32_scalar("nv", 2, &mut archive)?,
+                    i32_scalar("uOrder", 2, &mut archive)?,
+                    i32_scalar("vOrder", 2, &mut archive)?,
+                    array(
+                        "uKnot",
+                        F32_TYPE,
+                        PodArray::F32(vec![0.0, 0.0, 1.0, 1.0]),
+                        &mut archive,
+                    )?,
+                    array(
+                        "vKnot",
+                        F32_TYPE,
+                        PodArray::F32(vec![0.0, 0.0, 1.0, 1.0]),
+                        &mut archive,
+                    )?,
+                ],
+            ),
+            (
+                "faceset",
+                "AbcGeom_FaceSet_v1",
+                vec![
+                    bounds_property(".selfBnds", 4.0, &mut archive)?,
+                    array(".faces", I32_TYPE, PodArray::I32(vec![0]), &mut archive)?,
+                ],
+            ),
+            (
+                "light",
+                "AbcGeom_Light_v1",
+                vec![bounds_property(".childBnds", 5.0, &mut archive)?],
+            ),
+            (
+                "camera",
+                "AbcGeom_Camera_v1",
+                vec![
+                    scalar_property(
+                        ".core",
+                        camera_core,
+                        MetaData::default(),
+                        0,
+                        &[PodArray::F64(vec![0.0; 16])],
+                        &mut archive,
+                    )?,
+                    bounds_property(".childBnds", 6.0, &mut archive)?,
+                ],
+            ),
+            ("unbounded_light", "AbcGeom_Light_v1", vec![]),
+        ];
+        let mut root = ObjectWriter::new_root();
+        for (name, schema, properties) in objects {
+            root.add_child(schema_object(name, schema, properties)?)?;
+        }
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        for (name, offset) in [
+            ("points", Some(1.0)),
+            ("subd", Some(2.0)),
+            ("nupatch", Some(3.0)),
+            ("faceset", Some(4.0)),
+            ("light", Some(5.0)),
+            ("camera", Some(6.0)),
+            ("unbounded_light", None),
+        ] {
+            let object = archive.load_object(&format!("/{}", name), &data)?;
+            let schema = Schema::parse(&object, &data, &archive)?;
+            let bounds = schema.load_bounds_sample(0, &data)?;
+            assert_eq!(
+                bounds.map(|x| (x.min, x.max)),
+                offset.map(|x| (unit_bounds(x).min, unit_bounds(x).max)),
+                "{}",
+                name
+            );
+        }
+
+        Ok(())
     }
 }
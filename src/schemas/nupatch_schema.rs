@@ -0,0 +1,321 @@
+use super::base_geom_schema::BaseGeomSchema;
+use super::geom_param::GeomParam;
+use super::{load_f32_sample, load_i32_sample, load_i32_scalar_sample};
+use crate::object_reader::ObjectReader;
+use crate::pod::*;
+use crate::property::*;
+use crate::reader::ArchiveReader;
+use crate::result::*;
+use crate::time_sampling::SampleSelector;
+use crate::Archive;
+pub use std::convert::TryInto;
+
+// Trim curves are grouped in loops, the per curve arrays are stored back to back for all
+// loops.
+#[derive(Debug, Clone)]
+pub struct NuPatchTrimCurve {
+    pub loop_count: i32,
+    pub curve_counts: Vec<i32>,
+    pub vertex_counts: Vec<i32>,
+    pub orders: Vec<i32>,
+    pub knots: Vec<f32>,
+    pub min: Vec<f32>,
+    pub max: Vec<f32>,
+    pub u: Vec<f32>,
+    pub v: Vec<f32>,
+    pub w: Vec<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NuPatchSample {
+    pub positions: Vec<[f32; 3]>,
+    pub position_weights: Option<Vec<f32>>,
+
+    pub num_u: i32,
+    pub num_v: i32,
+    pub u_order: i32,
+    pub v_order: i32,
+    pub u_knots: Vec<f32>,
+    pub v_knots: Vec<f32>,
+
+    // normals and uvs have their indices resolved, their scope is available on the schema
+    pub normals: Option<Vec<[f32; 3]>>,
+    pub uvs: Option<Vec<[f32; 2]>>,
+    pub velocities: Option<Vec<[f32; 3]>>,
+    pub trim_curve: Option<NuPatchTrimCurve>,
+}
+
+#[derive(Debug)]
+struct TrimCurveProperties {
+    loop_count: ScalarPropertyReader,
+    curve_counts: ArrayPropertyReader,
+    vertex_counts: ArrayPropertyReader,
+    orders: ArrayPropertyReader,
+    knots: ArrayPropertyReader,
+    min: ArrayPropertyReader,
+    max: ArrayPropertyReader,
+    u: ArrayPropertyReader,
+    v: ArrayPropertyReader,
+    w: ArrayPropertyReader,
+}
+
+#[derive(Debug)]
+pub struct NuPatchSchema {
+    base_geom: BaseGeomSchema,
+
+    positions: ArrayPropertyReader,
+    num_u: ScalarPropertyReader,
+    num_v: ScalarPropertyReader,
+    u_order: ScalarPropertyReader,
+    v_order: ScalarPropertyReader,
+    u_knots: ArrayPropertyReader,
+    v_knots: ArrayPropertyReader,
+
+    position_weights: Option<ArrayPropertyReader>,
+    n: Option<GeomParam>,
+    uv: Option<GeomParam>,
+    velocities: Option<ArrayPropertyReader>,
+    trim_curve: Option<TrimCurveProperties>,
+    arb_geometry_parameters: Option<CompoundPropertyReader>,
+}
+
+impl NuPatchSchema {
+    pub fn new_from_object_reader(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let properties = object
+            .properties()
+            .ok_or(ParsingError::IncompatibleSchema)?;
+        let properties: CompoundPropertyReader = properties
+            .load_sub_property(0, reader, archive)?
+            .try_into()?;
+
+        let base_geom = BaseGeomSchema::new_from_properties(&properties, reader, archive)?;
+
+        let load_scalar = |name: &str, reader: &dyn ArchiveReader| -> Result<_> {
+            let property: ScalarPropertyReader = properties
+                .load_sub_property_by_name_checked(name, reader, archive, Some(&I32_TYPE))?
+                .ok_or(ParsingError::IncompatibleSchema)?
+                .try_into()?;
+            Ok(property)
+        };
+        let load_array =
+            |name: &str, data_type: &DataType, reader: &dyn ArchiveReader| -> Result<_> {
+                let property: ArrayPropertyReader = properties
+                    .load_sub_property_by_name_checked(name, reader, archive, Some(data_type))?
+                    .ok_or(ParsingError::IncompatibleSchema)?
+                    .try_into()?;
+                Ok(property)
+            };
+
+        // load required properties
+        let positions = load_array("P", &F32X3_TYPE, reader)?;
+        let num_u = load_scalar("nu", reader)?;
+        let num_v = load_scalar("nv", reader)?;
+        let u_order = load_scalar("uOrder", reader)?;
+        let v_order = load_scalar("vOrder", reader)?;
+        let u_knots = load_array("uKnot", &F32_TYPE, reader)?;
+        let v_knots = load_array("vKnot", &F32_TYPE, reader)?;
+
+        // load optional properties
+        let position_weights = properties
+            .load_sub_property_by_name_checked("w", reader, archive, Some(&F32_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let n = GeomParam::load_by_name_checked(&properties, "N", reader, archive, &F32X3_TYPE)?;
+        let uv = GeomParam::load_by_name_checked(&properties, "uv", reader, archive, &F32X2_TYPE)?;
+        let velocities = properties
+            .load_sub_property_by_name_checked(".velocities", reader, archive, Some(&F32X3_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        // the trim curve properties are always written together
+        let trim_curve = if properties.find_sub_property_index("trim_nloops").is_some() {
+            Some(TrimCurveProperties {
+                loop_count: load_scalar("trim_nloops", reader)?,
+                curve_counts: load_array("trim_ncurves", &I32_TYPE, reader)?,
+                vertex_counts: load_array("trim_n", &I32_TYPE, reader)?,
+                orders: load_array("trim_order", &I32_TYPE, reader)?,
+                knots: load_array("trim_knot", &F32_TYPE, reader)?,
+                min: load_array("trim_min", &F32_TYPE, reader)?,
+                max: load_array("trim_max", &F32_TYPE, reader)?,
+                u: load_array("trim_u", &F32_TYPE, reader)?,
+                v: load_array("trim_v", &F32_TYPE, reader)?,
+                w: load_array("trim_w", &F32_TYPE, reader)?,
+            })
+        } else {
+            None
+        };
+
+        let arb_geometry_parameters = properties
+            .load_sub_property_by_name(".arbGeomParams", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        Ok(Self {
+            base_geom,
+            positions,
+            num_u,
+            num_v,
+            u_order,
+            v_order,
+            u_knots,
+            v_knots,
+            position_weights,
+            n,
+            uv,
+            velocities,
+            trim_curve,
+            arb_geometry_parameters,
+        })
+    }
+
+    pub fn load_arb_geometry_parameters(
+        &self,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<GeomParam>> {
+        GeomParam::load_all_optional(self.arb_geometry_parameters.as_ref(), reader, archive)
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.positions.is_constant()
+            && self.num_u.is_constant()
+            && self.num_v.is_constant()
+            && self.u_knots.is_constant()
+            && self.v_knots.is_constant()
+            && if let Some(w) = &self.position_weights {
+                w.is_constant()
+            } else {
+                true
+            }
+    }
+    pub fn sample_count(&self) -> u32 {
+        self.positions.sample_count()
+    }
+    pub fn has_position_weights(&self) -> bool {
+        self.position_weights.is_some()
+    }
+    pub fn has_n(&self) -> bool {
+        self.n.is_some()
+    }
+    pub fn has_uv(&self) -> bool {
+        self.uv.is_some()
+    }
+    pub fn has_velocities(&self) -> bool {
+        self.velocities.is_some()
+    }
+    pub fn normals(&self) -> Option<&GeomParam> {
+        self.n.as_ref()
+    }
+    pub fn uv(&self) -> Option<&GeomParam> {
+        self.uv.as_ref()
+    }
+    pub fn has_trim_curve(&self) -> bool {
+        self.trim_curve.is_some()
+    }
+
+    pub fn load_bounds_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<BoundingBox> {
+        self.base_geom.load_bounds_sample(sample, reader)
+    }
+
+    pub fn load_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<NuPatchSample> {
+        let sample = sample.into();
+        let positions = {
+            let pod_array = self.positions.load_sample(sample, reader)?;
+            if let PodArray::F32(array) = pod_array {
+                chunk_vector_by_3(array)?
+            } else {
+                return Err(InternalError::Unreachable.into());
+            }
+        };
+
+        let velocities = if let Some(velocities) = &self.velocities {
+            let pod_array = velocities.load_sample(sample, reader)?;
+            if let PodArray::F32(array) = pod_array {
+                Some(chunk_vector_by_3(array)?)
+            } else {
+                return Err(InternalError::Unreachable.into());
+            }
+        } else {
+            None
+        };
+
+        let normals = if let Some(n) = &self.n {
+            let pod_array = n.load_expanded_sample(sample, reader)?;
+            if let PodArray::F32(array) = pod_array {
+                Some(chunk_vector_by_3(array)?)
+            } else {
+                return Err(InternalError::Unreachable.into());
+            }
+        } else {
+            None
+        };
+
+        let uvs = if let Some(uv) = &self.uv {
+            let pod_array = uv.load_expanded_sample(sample, reader)?;
+            if let PodArray::F32(array) = pod_array {
+                Some(chunk_vector_by_2(array)?)
+            } else {
+                return Err(InternalError::Unreachable.into());
+            }
+        } else {
+            None
+        };
+
+        Ok(NuPatchSample {
+            positions,
+            position_weights: self
+                .position_weights
+                .as_ref()
+                .map(|x| load_f32_sample(x, sample, reader))
+                .transpose()?,
+            num_u: load_i32_scalar_sample(&self.num_u, sample, reader)?,
+            num_v: load_i32_scalar_sample(&self.num_v, sample, reader)?,
+            u_order: load_i32_scalar_sample(&self.u_order, sample, reader)?,
+            v_order: load_i32_scalar_sample(&self.v_order, sample, reader)?,
+            u_knots: load_f32_sample(&self.u_knots, sample, reader)?,
+            v_knots: load_f32_sample(&self.v_knots, sample, reader)?,
+            normals,
+            uvs,
+            velocities,
+            trim_curve: self
+                .trim_curve
+                .as_ref()
+                .map(|x| x.load_sample(sample, reader))
+                .transpose()?,
+        })
+    }
+}
+
+impl TrimCurveProperties {
+    fn load_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<NuPatchTrimCurve> {
+        let sample = sample.into();
+        Ok(NuPatchTrimCurve {
+            loop_count: load_i32_scalar_sample(&self.loop_count, sample, reader)?,
+            curve_counts: load_i32_sample(&self.curve_counts, sample, reader)?,
+            vertex_counts: load_i32_sample(&self.vertex_counts, sample, reader)?,
+            orders: load_i32_sample(&self.orders, sample, reader)?,
+            knots: load_f32_sample(&self.knots, sample, reader)?,
+            min: load_f32_sample(&self.min, sample, reader)?,
+            max: load_f32_sample(&self.max, sample, reader)?,
+            u: load_f32_sample(&self.u, sample, reader)?,
+            v: load_f32_sample(&self.v, sample, reader)?,
+            w: load_f32_sample(&self.w, sample, reader)?,
+        })
+    }
+}
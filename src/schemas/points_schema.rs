@@ -0,0 +1,162 @@
+use super::base_geom_schema::BaseGeomSchema;
+use super::geom_param::GeomParam;
+use crate::object_reader::ObjectReader;
+use crate::pod::*;
+use crate::property::*;
+use crate::reader::ArchiveReader;
+use crate::result::*;
+use crate::time_sampling::SampleSelector;
+use crate::Archive;
+pub use std::convert::TryInto;
+
+#[derive(Debug)]
+pub struct PointsSchema {
+    base_geom: BaseGeomSchema,
+
+    positions: ArrayPropertyReader,
+    ids: ArrayPropertyReader,
+
+    velocities: Option<ArrayPropertyReader>,
+    widths: Option<GeomParam>,
+    arb_geometry_parameters: Option<CompoundPropertyReader>,
+}
+
+impl PointsSchema {
+    pub fn new_from_object_reader(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let properties = object
+            .properties()
+            .ok_or(ParsingError::IncompatibleSchema)?;
+        let properties: CompoundPropertyReader = properties
+            .load_sub_property(0, reader, archive)?
+            .try_into()?;
+
+        let base_geom = BaseGeomSchema::new_from_properties(&properties, reader, archive)?;
+
+        // load required properties
+        let positions: ArrayPropertyReader = properties
+            .load_sub_property_by_name_checked("P", reader, archive, Some(&F32X3_TYPE))?
+            .ok_or(ParsingError::IncompatibleSchema)?
+            .try_into()?;
+        let ids: ArrayPropertyReader = properties
+            .load_sub_property_by_name_checked(".pointIds", reader, archive, Some(&U64_TYPE))?
+            .ok_or(ParsingError::IncompatibleSchema)?
+            .try_into()?;
+
+        // load optional properties
+        let velocities = properties
+            .load_sub_property_by_name_checked(".velocities", reader, archive, Some(&F32X3_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let widths =
+            GeomParam::load_by_name_checked(&properties, ".widths", reader, archive, &F32_TYPE)?;
+        let arb_geometry_parameters = properties
+            .load_sub_property_by_name(".arbGeomParams", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        Ok(Self {
+            base_geom,
+            positions,
+            ids,
+            velocities,
+            widths,
+            arb_geometry_parameters,
+        })
+    }
+
+    pub fn load_arb_geometry_parameters(
+        &self,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<GeomParam>> {
+        GeomParam::load_all_optional(self.arb_geometry_parameters.as_ref(), reader, archive)
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.positions.is_constant() && self.ids.is_constant()
+    }
+    pub fn sample_count(&self) -> u32 {
+        self.positions.sample_count()
+    }
+    pub fn has_velocities(&self) -> bool {
+        self.velocities.is_some()
+    }
+    pub fn has_widths(&self) -> bool {
+        self.widths.is_some()
+    }
+
+    pub fn load_bounds_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<BoundingBox> {
+        self.base_geom.load_bounds_sample(sample, reader)
+    }
+
+    pub fn load_positions_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<[f32; 3]>> {
+        let pod_array = self.positions.load_sample(sample, reader)?;
+        let pod_array = if let PodArray::F32(array) = pod_array {
+            array
+        } else {
+            return Err(InternalError::Unreachable.into());
+        };
+
+        Ok(chunk_vector_by_3(pod_array)?)
+    }
+
+    pub fn load_ids_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<u64>> {
+        let pod_array = self.ids.load_sample(sample, reader)?;
+        if let PodArray::U64(array) = pod_array {
+            Ok(array)
+        } else {
+            Err(InternalError::Unreachable.into())
+        }
+    }
+
+    pub fn load_velocities_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<[f32; 3]>>> {
+        if let Some(velocities) = &self.velocities {
+            let array = velocities.load_sample(sample, reader)?;
+            if let PodArray::F32(array) = array {
+                Ok(Some(chunk_vector_by_3(array)?))
+            } else {
+                Err(InternalError::Unreachable.into())
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Loads the widths with their indices resolved, constant widths hold a single value.
+    pub fn load_widths_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<f32>>> {
+        if let Some(widths) = &self.widths {
+            let array = widths.load_expanded_sample(sample, reader)?;
+            if let PodArray::F32(array) = array {
+                Ok(Some(array))
+            } else {
+                Err(InternalError::Unreachable.into())
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
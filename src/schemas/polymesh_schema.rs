@@ -1,9 +1,13 @@
 use super::base_geom_schema::BaseGeomSchema;
-use crate::object_reader::ObjectReader;
+use super::face_set_schema::{find_face_sets, load_face_set, FaceSetSchema};
+use super::geom_param::{expand_to_face_varying, GeomParam};
+use super::{load_interpolated_positions, TopologyVariance};
+use crate::object_reader::{ObjectHeader, ObjectReader};
 use crate::pod::*;
 use crate::property::*;
 use crate::reader::ArchiveReader;
 use crate::result::*;
+use crate::time_sampling::SampleSelector;
 use crate::Archive;
 pub use std::convert::TryInto;
 
@@ -13,15 +17,20 @@ pub struct PolyMeshSchema {
     pub facecounts: ArrayPropertyReader,
     pub faceindices: ArrayPropertyReader,
     pub vertices: ArrayPropertyReader,
-    pub normals: Option<ArrayPropertyReader>,
-    pub uv: Option<CompoundPropertyReader>,
+    pub normals: Option<GeomParam>,
+    pub uv: Option<GeomParam>,
     pub velocities: Option<ArrayPropertyReader>,
+    pub arb_geometry_parameters: Option<CompoundPropertyReader>,
+    pub user_properties: Option<CompoundPropertyReader>,
+    // uv sets besides `uv`, found in the arbitrary geometry parameters
+    uv_sets: Vec<GeomParam>,
+    face_sets: Vec<ObjectHeader>,
 }
 
 impl PolyMeshSchema {
     pub fn new_from_object_reader(
         object: &ObjectReader,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         archive: &Archive,
     ) -> Result<Self> {
         let properties = object
@@ -51,21 +60,31 @@ impl PolyMeshSchema {
             .try_into()?;
 
         // load optional properties
-        let normals = properties
-            .load_sub_property_by_name_checked("N", reader, archive, Some(&F32X3_TYPE))?
+        let normals =
+            GeomParam::load_by_name_checked(&properties, "N", reader, archive, &F32X3_TYPE)?;
+        let uv = GeomParam::load_by_name_checked(&properties, "uv", reader, archive, &F32X2_TYPE)?;
+
+        let velocities = properties
+            .load_sub_property_by_name_checked(".velocities", reader, archive, Some(&F32X3_TYPE))?
             .map(|x| x.try_into())
             .transpose()?;
 
-        let uv = properties
-            .load_sub_property_by_name("uv", reader, archive)?
+        let arb_geometry_parameters = properties
+            .load_sub_property_by_name(".arbGeomParams", reader, archive)?
             .map(|x| x.try_into())
             .transpose()?;
 
-        let velocities = properties
-            .load_sub_property_by_name_checked("velocities", reader, archive, Some(&F32X3_TYPE))?
+        let user_properties = properties
+            .load_sub_property_by_name(".userProperties", reader, archive)?
             .map(|x| x.try_into())
             .transpose()?;
 
+        let uv_sets =
+            GeomParam::load_all_optional(arb_geometry_parameters.as_ref(), reader, archive)?
+                .into_iter()
+                .filter(is_uv_set)
+                .collect();
+
         Ok(Self {
             base_geom,
             facecounts,
@@ -74,9 +93,25 @@ impl PolyMeshSchema {
             normals,
             uv,
             velocities,
+            arb_geometry_parameters,
+            user_properties,
+            uv_sets,
+            face_sets: find_face_sets(object),
         })
     }
 
+    pub fn topology_variance(&self) -> TopologyVariance {
+        if self.facecounts.is_constant() && self.faceindices.is_constant() {
+            if self.vertices.is_constant() {
+                TopologyVariance::ConstantTopology
+            } else {
+                TopologyVariance::HomogeneousTopology
+            }
+        } else {
+            TopologyVariance::HeterogeneousTopology
+        }
+    }
+
     pub fn has_normals(&self) -> bool {
         self.normals.is_some()
     }
@@ -89,20 +124,47 @@ impl PolyMeshSchema {
         self.velocities.is_some()
     }
 
+    pub fn load_arb_geometry_parameters(
+        &self,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<GeomParam>> {
+        GeomParam::load_all_optional(self.arb_geometry_parameters.as_ref(), reader, archive)
+    }
+
+    pub fn face_sets(&self) -> &[ObjectHeader] {
+        &self.face_sets
+    }
+
+    // Loads one of the face sets of this mesh, `object` is the object the schema was read from.
+    pub fn load_face_set(
+        &self,
+        name: &str,
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<FaceSetSchema> {
+        if !self.face_sets.iter().any(|face_set| face_set.name == name) {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        load_face_set(object, name, reader, archive)
+    }
+
     pub fn load_bounds_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<BoundingBox> {
-        self.base_geom.load_bounds_sample(sample_index, reader)
+        self.base_geom.load_bounds_sample(sample, reader)
     }
 
     pub fn load_vertices_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Vec<[f32; 3]>> {
-        let pod_array = self.vertices.load_sample(sample_index, reader)?;
+        let pod_array = self.vertices.load_sample(sample, reader)?;
         let pod_array = if let PodArray::F32(array) = pod_array {
             array
         } else {
@@ -112,12 +174,27 @@ impl PolyMeshSchema {
         Ok(chunk_vector_by_3(pod_array)?)
     }
 
+    // Loads the vertices at `time`, blending the samples around it when the topology allows it.
+    pub fn load_interpolated_vertices(
+        &self,
+        time: f64,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<[f32; 3]>> {
+        load_interpolated_positions(
+            &self.vertices,
+            self.velocities.as_ref(),
+            self.topology_variance(),
+            time,
+            reader,
+        )
+    }
+
     pub fn load_facecounts_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Vec<i32>> {
-        let pod_array = self.facecounts.load_sample(sample_index, reader)?;
+        let pod_array = self.facecounts.load_sample(sample, reader)?;
         if let PodArray::I32(array) = pod_array {
             Ok(array)
         } else {
@@ -127,14 +204,295 @@ impl PolyMeshSchema {
 
     pub fn load_faceindices_sample(
         &self,
-        sample_index: u32,
-        reader: &mut dyn ArchiveReader,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
     ) -> Result<Vec<i32>> {
-        let pod_array = self.faceindices.load_sample(sample_index, reader)?;
+        let pod_array = self.faceindices.load_sample(sample, reader)?;
         if let PodArray::I32(array) = pod_array {
             Ok(array)
         } else {
             Err(InternalError::Unreachable.into())
         }
     }
+
+    // Loads a normal per face vertex, resolving indices and the geometry scope.
+    pub fn load_normals_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<[f32; 3]>>> {
+        let sample = sample.into();
+        if let Some(normals) = &self.normals {
+            let array = normals.load_expanded_sample(sample, reader)?;
+            let array = if let PodArray::F32(array) = array {
+                chunk_vector_by_3(array)?
+            } else {
+                return Err(InternalError::Unreachable.into());
+            };
+            expand_to_face_varying(
+                array,
+                normals.scope(),
+                || self.load_facecounts_sample(sample, reader),
+                || self.load_faceindices_sample(sample, reader),
+            )
+            .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Loads a uv per face vertex, resolving indices and the geometry scope.
+    pub fn load_uv_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<[f32; 2]>>> {
+        self.uv
+            .as_ref()
+            .map(|uv| self.load_uv_geom_param_sample(uv, sample, reader))
+            .transpose()
+    }
+
+    // Names of all uv sets, starting with `uv` when the mesh has it.
+    pub fn uv_set_names(&self) -> Vec<&str> {
+        self.uv
+            .iter()
+            .chain(&self.uv_sets)
+            .map(|uv| uv.name())
+            .collect()
+    }
+
+    pub fn load_uv_set_sample(
+        &self,
+        name: &str,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<[f32; 2]>> {
+        let uv = self
+            .uv
+            .iter()
+            .chain(&self.uv_sets)
+            .find(|uv| uv.name() == name)
+            .ok_or(UserError::InvalidParameter)?;
+        self.load_uv_geom_param_sample(uv, sample, reader)
+    }
+
+    pub fn load_velocities_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<[f32; 3]>>> {
+        if let Some(velocities) = &self.velocities {
+            let array = velocities.load_sample(sample, reader)?;
+            if let PodArray::F32(array) = array {
+                Ok(Some(chunk_vector_by_3(array)?))
+            } else {
+                Err(InternalError::Unreachable.into())
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn load_uv_geom_param_sample(
+        &self,
+        uv: &GeomParam,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<[f32; 2]>> {
+        let sample = sample.into();
+        let array = uv.load_expanded_sample(sample, reader)?;
+        let array = if let PodArray::F32(array) = array {
+            chunk_vector_by_2(array)?
+        } else {
+            return Err(InternalError::Unreachable.into());
+        };
+        expand_to_face_varying(
+            array,
+            uv.scope(),
+            || self.load_facecounts_sample(sample, reader),
+            || self.load_faceindices_sample(sample, reader),
+        )
+    }
+}
+
+// Alembic does not mark uv sets, two component float parameters are taken as uv sets when they
+// are named like one: `st`, or a name starting with `uv` such as `uv1`.
+fn is_uv_set(geom_param: &GeomParam) -> bool {
+    geom_param.data_type() == &F32X2_TYPE
+        && (geom_param.name() == "st" || geom_param.name().starts_with("uv"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive_writer::ArchiveWriter;
+    use crate::metadata::MetaData;
+    use crate::object_writer::ObjectWriter;
+    use crate::schemas::geom_param_meta_data;
+    use crate::schemas::tests::{array_property, scalar_property, schema_object, TestArchive};
+    use std::io::Cursor;
+
+    // A single triangle with the given properties next to its topology.
+    fn write_triangle(
+        properties: impl FnOnce(&mut TestArchive) -> Result<Vec<PropertyWriter>>,
+    ) -> Result<Vec<u8>> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut mesh_properties = vec![
+            scalar_property(
+                ".selfBnds",
+                BOX_TYPE,
+                MetaData::default(),
+                0,
+                &[BoundingBox::from_points(&[[0.0, 0.0, 0.0], [1.0, 1.0, 0.0]]).to_pod_array()],
+                &mut archive,
+            )?,
+            array_property(
+                "P",
+                F32X3_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::F32(vec![
+                    0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0,
+                ])],
+                &mut archive,
+            )?,
+            array_property(
+                ".faceIndices",
+                I32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::I32(vec![0, 1, 2])],
+                &mut archive,
+            )?,
+            array_property(
+                ".faceCounts",
+                I32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::I32(vec![3])],
+                &mut archive,
+            )?,
+        ];
+        mesh_properties.extend(properties(&mut archive)?);
+
+        let mut root = ObjectWriter::new_root();
+        root.add_child(schema_object(
+            "mesh",
+            "AbcGeom_PolyMesh_v1",
+            mesh_properties,
+        )?)?;
+        Ok(archive.finish(root)?.into_inner())
+    }
+
+    fn load_mesh(data: &Vec<u8>) -> Result<PolyMeshSchema> {
+        let archive = Archive::new(data)?;
+        let object = archive.load_object("/mesh", data)?;
+        PolyMeshSchema::new_from_object_reader(&object, data, &archive)
+    }
+
+    #[test]
+    fn uv_sets_are_found_by_name() -> Result<()> {
+        let data = write_triangle(|archive| {
+            let mut arb_geometry_parameters =
+                CompoundPropertyWriter::new(".arbGeomParams", MetaData::default());
+            let uv = PodArray::F32(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0]);
+            for (name, data_type, sample) in [
+                ("uv1", F32X2_TYPE, uv.clone()),
+                ("st", F32X2_TYPE, uv.clone()),
+                ("offset", F32X2_TYPE, uv),
+                ("uvw", F32X3_TYPE, PodArray::F32(vec![0.0; 9])),
+            ] {
+                arb_geometry_parameters.add_sub_property(array_property(
+                    name,
+                    data_type,
+                    geom_param_meta_data("fvr", ""),
+                    0,
+                    &[sample],
+                    archive,
+                )?)?;
+            }
+            Ok(vec![arb_geometry_parameters.into()])
+        })?;
+
+        let mesh = load_mesh(&data)?;
+
+        let mut uv_set_names = mesh.uv_set_names();
+        uv_set_names.sort_unstable();
+        assert_eq!(uv_set_names, ["st", "uv1"]);
+        assert_eq!(
+            mesh.load_uv_set_sample("uv1", 0, &data)?,
+            [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]
+        );
+        assert!(mesh.load_uv_set_sample("offset", 0, &data).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn uvs_and_normals_are_loaded_per_face_vertex() -> Result<()> {
+        // flat face varying uvs and indexed vertex normals
+        let data = write_triangle(|archive| {
+            let mut normals =
+                CompoundPropertyWriter::new("N", geom_param_meta_data("vtx", "normal"));
+            normals.add_sub_property(array_property(
+                ".vals",
+                F32X3_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::F32(vec![0.0, 0.0, 1.0, 0.0, 1.0, 0.0])],
+                archive,
+            )?)?;
+            normals.add_sub_property(array_property(
+                ".indices",
+                U32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::U32(vec![1, 0, 1])],
+                archive,
+            )?)?;
+            Ok(vec![
+                array_property(
+                    "uv",
+                    F32X2_TYPE,
+                    geom_param_meta_data("fvr", "vector"),
+                    0,
+                    &[PodArray::F32(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0])],
+                    archive,
+                )?,
+                normals.into(),
+            ])
+        })?;
+        let mesh = load_mesh(&data)?;
+        assert!(!mesh.uv.as_ref().unwrap().is_indexed());
+        assert!(mesh.normals.as_ref().unwrap().is_indexed());
+        assert_eq!(
+            mesh.load_uv_sample(0, &data)?,
+            Some(vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]])
+        );
+        assert_eq!(
+            mesh.load_normals_sample(0, &data)?,
+            Some(vec![[0.0, 1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]])
+        );
+
+        // a single normal for the face
+        let data = write_triangle(|archive| {
+            Ok(vec![array_property(
+                "N",
+                F32X3_TYPE,
+                geom_param_meta_data("uni", "normal"),
+                0,
+                &[PodArray::F32(vec![0.0, 0.0, 1.0])],
+                archive,
+            )?])
+        })?;
+        let mesh = load_mesh(&data)?;
+        assert_eq!(mesh.load_uv_sample(0, &data)?, None);
+        assert_eq!(
+            mesh.load_normals_sample(0, &data)?,
+            Some(vec![[0.0, 0.0, 1.0]; 3])
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,346 @@
+use super::{
+    add_optional_sample, geom_param_meta_data, interpretation_meta_data, schema_meta_data,
+    schema_object_meta_data, GEOM_BASE_SCHEMA,
+};
+use crate::archive_writer::ArchiveWriter;
+use crate::metadata::MetaData;
+use crate::object_writer::ObjectWriter;
+use crate::pod::*;
+use crate::property::*;
+use crate::result::*;
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+
+const POLYMESH_SCHEMA: &str = "AbcGeom_PolyMesh_v1";
+
+#[derive(Debug, Default)]
+pub struct PolyMeshSample<'a> {
+    pub positions: &'a [[f32; 3]],
+    pub face_indices: &'a [i32],
+    pub face_counts: &'a [i32],
+
+    // normals and uvs are stored per face vertex, velocities per position
+    pub normals: Option<&'a [[f32; 3]]>,
+    pub uvs: Option<&'a [[f32; 2]]>,
+    pub velocities: Option<&'a [[f32; 3]]>,
+}
+
+#[derive(Debug)]
+pub struct PolyMeshWriter {
+    name: String,
+    time_sampling_index: u32,
+
+    self_bounds: ScalarPropertyWriter,
+    positions: ArrayPropertyWriter,
+    face_indices: ArrayPropertyWriter,
+    face_counts: ArrayPropertyWriter,
+
+    normals: Option<ArrayPropertyWriter>,
+    uv_vals: Option<ArrayPropertyWriter>,
+    uv_indices: Option<ArrayPropertyWriter>,
+    velocities: Option<ArrayPropertyWriter>,
+}
+
+impl PolyMeshWriter {
+    pub fn new(name: &str, time_sampling_index: u32) -> Self {
+        Self {
+            name: name.to_owned(),
+            time_sampling_index,
+
+            self_bounds: ScalarPropertyWriter::new(
+                ".selfBnds",
+                BOX_TYPE,
+                interpretation_meta_data("box"),
+                time_sampling_index,
+            ),
+            positions: ArrayPropertyWriter::new(
+                "P",
+                F32X3_TYPE,
+                interpretation_meta_data("point"),
+                time_sampling_index,
+            ),
+            face_indices: ArrayPropertyWriter::new(
+                ".faceIndices",
+                I32_TYPE,
+                MetaData::default(),
+                time_sampling_index,
+            ),
+            face_counts: ArrayPropertyWriter::new(
+                ".faceCounts",
+                I32_TYPE,
+                MetaData::default(),
+                time_sampling_index,
+            ),
+
+            normals: None,
+            uv_vals: None,
+            uv_indices: None,
+            velocities: None,
+        }
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.positions.sample_count()
+    }
+
+    pub fn add_sample<W: Write + Seek>(
+        &mut self,
+        sample: &PolyMeshSample,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<()> {
+        let face_vertex_count = sample.face_indices.len();
+        if sample.face_counts.iter().map(|&x| x as i64).sum::<i64>() != face_vertex_count as i64
+            || sample
+                .face_indices
+                .iter()
+                .any(|&x| x < 0 || x as usize >= sample.positions.len())
+            || sample
+                .normals
+                .is_some_and(|normals| normals.len() != face_vertex_count)
+            || sample.uvs.is_some_and(|uvs| uvs.len() != face_vertex_count)
+            || sample
+                .velocities
+                .is_some_and(|velocities| velocities.len() != sample.positions.len())
+        {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        let sample_index = self.sample_count();
+        let time_sampling_index = self.time_sampling_index;
+
+        self.self_bounds.add_sample(
+            &BoundingBox::from_points(sample.positions).to_pod_array(),
+            archive,
+        )?;
+        self.positions.add_sample(
+            &PodArray::F32(sample.positions.iter().flatten().copied().collect()),
+            archive,
+        )?;
+        self.face_indices
+            .add_sample(&PodArray::I32(sample.face_indices.to_vec()), archive)?;
+        self.face_counts
+            .add_sample(&PodArray::I32(sample.face_counts.to_vec()), archive)?;
+
+        add_optional_sample(
+            &mut self.normals,
+            sample
+                .normals
+                .map(|normals| PodArray::F32(normals.iter().flatten().copied().collect())),
+            sample_index,
+            || {
+                ArrayPropertyWriter::new(
+                    "N",
+                    F32X3_TYPE,
+                    geom_param_meta_data("fvr", "normal"),
+                    time_sampling_index,
+                )
+            },
+            archive,
+        )?;
+
+        // uvs are written as an indexed geometry parameter, sharing identical values
+        let uvs = sample.uvs.map(index_uvs);
+        add_optional_sample(
+            &mut self.uv_vals,
+            uvs.as_ref().map(|(vals, _)| PodArray::F32(vals.clone())),
+            sample_index,
+            || {
+                ArrayPropertyWriter::new(
+                    ".vals",
+                    F32X2_TYPE,
+                    interpretation_meta_data("vector"),
+                    time_sampling_index,
+                )
+            },
+            archive,
+        )?;
+        add_optional_sample(
+            &mut self.uv_indices,
+            uvs.map(|(_, indices)| PodArray::U32(indices)),
+            sample_index,
+            || {
+                ArrayPropertyWriter::new(
+                    ".indices",
+                    U32_TYPE,
+                    MetaData::default(),
+                    time_sampling_index,
+                )
+            },
+            archive,
+        )?;
+
+        add_optional_sample(
+            &mut self.velocities,
+            sample
+                .velocities
+                .map(|velocities| PodArray::F32(velocities.iter().flatten().copied().collect())),
+            sample_index,
+            || {
+                ArrayPropertyWriter::new(
+                    ".velocities",
+                    F32X3_TYPE,
+                    interpretation_meta_data("vector"),
+                    time_sampling_index,
+                )
+            },
+            archive,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn into_object_writer(self) -> Result<ObjectWriter> {
+        let mut geom = CompoundPropertyWriter::new(
+            ".geom",
+            schema_meta_data(POLYMESH_SCHEMA, GEOM_BASE_SCHEMA),
+        );
+        geom.add_sub_property(self.self_bounds)?;
+        geom.add_sub_property(self.positions)?;
+        geom.add_sub_property(self.face_indices)?;
+        geom.add_sub_property(self.face_counts)?;
+        if let Some(normals) = self.normals {
+            geom.add_sub_property(normals)?;
+        }
+        if let (Some(vals), Some(indices)) = (self.uv_vals, self.uv_indices) {
+            let mut meta_data = geom_param_meta_data("fvr", "vector");
+            meta_data
+                .tokens
+                .insert("podName".to_owned(), "float32_t".to_owned());
+            meta_data
+                .tokens
+                .insert("podExtent".to_owned(), "2".to_owned());
+
+            let mut uv = CompoundPropertyWriter::new("uv", meta_data);
+            uv.add_sub_property(vals)?;
+            uv.add_sub_property(indices)?;
+            geom.add_sub_property(uv)?;
+        }
+        if let Some(velocities) = self.velocities {
+            geom.add_sub_property(velocities)?;
+        }
+
+        let mut object = ObjectWriter::new(
+            &self.name,
+            schema_object_meta_data(POLYMESH_SCHEMA, GEOM_BASE_SCHEMA, ".geom"),
+        );
+        object.properties_mut().add_sub_property(geom)?;
+        Ok(object)
+    }
+}
+
+fn index_uvs(uvs: &[[f32; 2]]) -> (Vec<f32>, Vec<u32>) {
+    let mut vals = vec![];
+    let mut indices = Vec::with_capacity(uvs.len());
+    let mut index_map = HashMap::<[u32; 2], u32>::default();
+
+    for uv in uvs {
+        let index = *index_map
+            .entry([uv[0].to_bits(), uv[1].to_bits()])
+            .or_insert_with(|| {
+                vals.extend_from_slice(uv);
+                (vals.len() / 2 - 1) as u32
+            });
+        indices.push(index);
+    }
+
+    (vals, indices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::PolyMeshSchema;
+    use crate::Archive;
+    use std::io::Cursor;
+
+    #[test]
+    fn meshes_round_trip() -> Result<()> {
+        // two triangles sharing an edge, the uvs repeat on the shared vertices
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [1.0, 1.0, 2.0],
+        ];
+        let face_indices = [0, 1, 2, 2, 1, 3];
+        let uvs = [
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [0.0, 1.0],
+            [0.0, 1.0],
+            [1.0, 0.0],
+            [1.0, 1.0],
+        ];
+        let normals = [[0.0, 0.0, 1.0]; 6];
+        let velocities = [[1.0, 0.0, 0.0]; 4];
+        let sample = PolyMeshSample {
+            positions: &positions,
+            face_indices: &face_indices,
+            face_counts: &[3, 3],
+            normals: Some(&normals),
+            uvs: Some(&uvs),
+            velocities: Some(&velocities),
+        };
+
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut mesh = PolyMeshWriter::new("mesh", 0);
+        mesh.add_sample(&sample, &mut archive)?;
+
+        // the topology and the per face vertex values have to agree
+        let invalid_samples = [
+            PolyMeshSample {
+                face_counts: &[3, 2],
+                ..sample
+            },
+            PolyMeshSample {
+                face_indices: &[0, 1, 2, 2, 1, 4],
+                ..sample
+            },
+            PolyMeshSample {
+                uvs: Some(&uvs[..4]),
+                ..sample
+            },
+            PolyMeshSample {
+                normals: Some(&normals[..4]),
+                ..sample
+            },
+            PolyMeshSample {
+                velocities: Some(&velocities[..3]),
+                ..sample
+            },
+        ];
+        for invalid_sample in &invalid_samples {
+            assert!(mesh.add_sample(invalid_sample, &mut archive).is_err());
+        }
+
+        let mut root = ObjectWriter::new_root();
+        root.add_child(mesh.into_object_writer()?)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let object = archive.load_object("/mesh", &data)?;
+        let mesh = PolyMeshSchema::new_from_object_reader(&object, &data, &archive)?;
+
+        assert_eq!(mesh.load_vertices_sample(0, &data)?, positions);
+        assert_eq!(mesh.load_faceindices_sample(0, &data)?, face_indices);
+        assert_eq!(mesh.load_facecounts_sample(0, &data)?, [3, 3]);
+        let bounds = mesh.load_bounds_sample(0, &data)?;
+        assert_eq!(bounds.min, [0.0, 0.0, 0.0]);
+        assert_eq!(bounds.max, [1.0, 1.0, 2.0]);
+
+        // the uvs only store their four distinct values
+        let uv = mesh.uv.as_ref().unwrap();
+        assert!(uv.is_indexed());
+        assert_eq!(
+            uv.load_values_sample(0, &data)?,
+            PodArray::F32(vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0])
+        );
+        assert_eq!(mesh.load_uv_sample(0, &data)?.unwrap(), uvs);
+
+        assert!(!mesh.normals.as_ref().unwrap().is_indexed());
+        assert_eq!(mesh.load_normals_sample(0, &data)?.unwrap(), normals);
+        assert_eq!(mesh.load_velocities_sample(0, &data)?.unwrap(), velocities);
+
+        Ok(())
+    }
+}
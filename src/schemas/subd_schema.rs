@@ -0,0 +1,574 @@
+use super::base_geom_schema::BaseGeomSchema;
+use super::face_set_schema::{find_face_sets, load_face_set, FaceSetSchema};
+use super::geom_param::{expand_to_face_varying, GeomParam};
+use super::{load_f32_sample, load_i32_sample, load_i32_scalar_sample};
+use crate::object_reader::{ObjectHeader, ObjectReader};
+use crate::pod::*;
+use crate::property::*;
+use crate::reader::ArchiveReader;
+use crate::result::*;
+use crate::time_sampling::SampleSelector;
+use crate::Archive;
+pub use std::convert::TryInto;
+
+#[derive(Debug)]
+pub struct SubDSchema {
+    base_geom: BaseGeomSchema,
+
+    positions: ArrayPropertyReader,
+    face_indices: ArrayPropertyReader,
+    face_counts: ArrayPropertyReader,
+
+    subdivision_scheme: Option<ScalarPropertyReader>,
+    face_varying_interpolate_boundary: Option<ScalarPropertyReader>,
+    face_varying_propagate_corners: Option<ScalarPropertyReader>,
+    interpolate_boundary: Option<ScalarPropertyReader>,
+
+    crease_indices: Option<ArrayPropertyReader>,
+    crease_lengths: Option<ArrayPropertyReader>,
+    crease_sharpnesses: Option<ArrayPropertyReader>,
+    corner_indices: Option<ArrayPropertyReader>,
+    corner_sharpnesses: Option<ArrayPropertyReader>,
+    holes: Option<ArrayPropertyReader>,
+
+    uv: Option<GeomParam>,
+    velocities: Option<ArrayPropertyReader>,
+    arb_geometry_parameters: Option<CompoundPropertyReader>,
+    face_sets: Vec<ObjectHeader>,
+}
+
+impl SubDSchema {
+    pub fn new_from_object_reader(
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Self> {
+        let properties = object
+            .properties()
+            .ok_or(ParsingError::IncompatibleSchema)?;
+        let properties: CompoundPropertyReader = properties
+            .load_sub_property(0, reader, archive)?
+            .try_into()?;
+
+        let base_geom = BaseGeomSchema::new_from_properties(&properties, reader, archive)?;
+
+        // load required properties
+        let positions: ArrayPropertyReader = properties
+            .load_sub_property_by_name_checked("P", reader, archive, Some(&F32X3_TYPE))?
+            .ok_or(ParsingError::IncompatibleSchema)?
+            .try_into()?;
+        let face_indices: ArrayPropertyReader = properties
+            .load_sub_property_by_name_checked(".faceIndices", reader, archive, Some(&I32_TYPE))?
+            .ok_or(ParsingError::IncompatibleSchema)?
+            .try_into()?;
+        let face_counts: ArrayPropertyReader = properties
+            .load_sub_property_by_name_checked(".faceCounts", reader, archive, Some(&I32_TYPE))?
+            .ok_or(ParsingError::IncompatibleSchema)?
+            .try_into()?;
+
+        // load optional properties, these are only written when they differ from their defaults
+        let subdivision_scheme = properties
+            .load_sub_property_by_name_checked(".scheme", reader, archive, Some(&STRING_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let face_varying_interpolate_boundary = properties
+            .load_sub_property_by_name_checked(
+                ".faceVaryingInterpolateBoundary",
+                reader,
+                archive,
+                Some(&I32_TYPE),
+            )?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let face_varying_propagate_corners = properties
+            .load_sub_property_by_name_checked(
+                ".faceVaryingPropagateCorners",
+                reader,
+                archive,
+                Some(&I32_TYPE),
+            )?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let interpolate_boundary = properties
+            .load_sub_property_by_name_checked(
+                ".interpolateBoundary",
+                reader,
+                archive,
+                Some(&I32_TYPE),
+            )?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        let crease_indices = properties
+            .load_sub_property_by_name_checked(".creaseIndices", reader, archive, Some(&I32_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let crease_lengths = properties
+            .load_sub_property_by_name_checked(".creaseLengths", reader, archive, Some(&I32_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let crease_sharpnesses = properties
+            .load_sub_property_by_name_checked(
+                ".creaseSharpnesses",
+                reader,
+                archive,
+                Some(&F32_TYPE),
+            )?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let corner_indices = properties
+            .load_sub_property_by_name_checked(".cornerIndices", reader, archive, Some(&I32_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let corner_sharpnesses = properties
+            .load_sub_property_by_name_checked(
+                ".cornerSharpnesses",
+                reader,
+                archive,
+                Some(&F32_TYPE),
+            )?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let holes = properties
+            .load_sub_property_by_name_checked(".holes", reader, archive, Some(&I32_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        let uv = GeomParam::load_by_name_checked(&properties, "uv", reader, archive, &F32X2_TYPE)?;
+        let velocities = properties
+            .load_sub_property_by_name_checked(".velocities", reader, archive, Some(&F32X3_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+        let arb_geometry_parameters = properties
+            .load_sub_property_by_name(".arbGeomParams", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
+
+        Ok(Self {
+            base_geom,
+            positions,
+            face_indices,
+            face_counts,
+            subdivision_scheme,
+            face_varying_interpolate_boundary,
+            face_varying_propagate_corners,
+            interpolate_boundary,
+            crease_indices,
+            crease_lengths,
+            crease_sharpnesses,
+            corner_indices,
+            corner_sharpnesses,
+            holes,
+            uv,
+            velocities,
+            arb_geometry_parameters,
+            face_sets: find_face_sets(object),
+        })
+    }
+
+    pub fn load_arb_geometry_parameters(
+        &self,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<GeomParam>> {
+        GeomParam::load_all_optional(self.arb_geometry_parameters.as_ref(), reader, archive)
+    }
+
+    pub fn is_constant(&self) -> bool {
+        self.positions.is_constant()
+            && self.face_indices.is_constant()
+            && self.face_counts.is_constant()
+    }
+    pub fn sample_count(&self) -> u32 {
+        self.positions.sample_count()
+    }
+    pub fn has_creases(&self) -> bool {
+        self.crease_indices.is_some()
+    }
+    pub fn has_corners(&self) -> bool {
+        self.corner_indices.is_some()
+    }
+    pub fn has_holes(&self) -> bool {
+        self.holes.is_some()
+    }
+    pub fn has_uv(&self) -> bool {
+        self.uv.is_some()
+    }
+    pub fn has_velocities(&self) -> bool {
+        self.velocities.is_some()
+    }
+
+    pub fn face_sets(&self) -> &[ObjectHeader] {
+        &self.face_sets
+    }
+
+    // Loads one of the face sets of this mesh, `object` is the object the schema was read from.
+    pub fn load_face_set(
+        &self,
+        name: &str,
+        object: &ObjectReader,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<FaceSetSchema> {
+        if !self.face_sets.iter().any(|face_set| face_set.name == name) {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        load_face_set(object, name, reader, archive)
+    }
+
+    pub fn load_bounds_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<BoundingBox> {
+        self.base_geom.load_bounds_sample(sample, reader)
+    }
+
+    pub fn load_positions_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<[f32; 3]>> {
+        let pod_array = self.positions.load_sample(sample, reader)?;
+        let pod_array = if let PodArray::F32(array) = pod_array {
+            array
+        } else {
+            return Err(InternalError::Unreachable.into());
+        };
+
+        Ok(chunk_vector_by_3(pod_array)?)
+    }
+
+    pub fn load_face_indices_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<i32>> {
+        load_i32_sample(&self.face_indices, sample, reader)
+    }
+
+    pub fn load_face_counts_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<i32>> {
+        load_i32_sample(&self.face_counts, sample, reader)
+    }
+
+    // Defaults to "catmull-clark" when the scheme is not stored.
+    pub fn load_subdivision_scheme_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<String> {
+        if let Some(subdivision_scheme) = &self.subdivision_scheme {
+            let array = subdivision_scheme.load_sample(sample, reader)?;
+            if let PodArray::String(array) = array {
+                array
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| ParsingError::InvalidAlembicFile.into())
+            } else {
+                Err(InternalError::Unreachable.into())
+            }
+        } else {
+            Ok("catmull-clark".to_owned())
+        }
+    }
+
+    pub fn load_face_varying_interpolate_boundary_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<i32> {
+        load_i32_setting(&self.face_varying_interpolate_boundary, sample, reader)
+    }
+
+    pub fn load_face_varying_propagate_corners_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<i32> {
+        load_i32_setting(&self.face_varying_propagate_corners, sample, reader)
+    }
+
+    pub fn load_interpolate_boundary_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<i32> {
+        load_i32_setting(&self.interpolate_boundary, sample, reader)
+    }
+
+    pub fn load_crease_indices_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<i32>>> {
+        self.crease_indices
+            .as_ref()
+            .map(|x| load_i32_sample(x, sample, reader))
+            .transpose()
+    }
+
+    pub fn load_crease_lengths_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<i32>>> {
+        self.crease_lengths
+            .as_ref()
+            .map(|x| load_i32_sample(x, sample, reader))
+            .transpose()
+    }
+
+    pub fn load_crease_sharpnesses_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<f32>>> {
+        self.crease_sharpnesses
+            .as_ref()
+            .map(|x| load_f32_sample(x, sample, reader))
+            .transpose()
+    }
+
+    pub fn load_corner_indices_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<i32>>> {
+        self.corner_indices
+            .as_ref()
+            .map(|x| load_i32_sample(x, sample, reader))
+            .transpose()
+    }
+
+    pub fn load_corner_sharpnesses_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<f32>>> {
+        self.corner_sharpnesses
+            .as_ref()
+            .map(|x| load_f32_sample(x, sample, reader))
+            .transpose()
+    }
+
+    pub fn load_holes_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<i32>>> {
+        self.holes
+            .as_ref()
+            .map(|x| load_i32_sample(x, sample, reader))
+            .transpose()
+    }
+
+    // The uvs as they are stored, indexed uvs keep their unique values and indices.
+    pub fn uv(&self) -> Option<&GeomParam> {
+        self.uv.as_ref()
+    }
+
+    // Loads a uv per face vertex, resolving indices and the geometry scope.
+    pub fn load_uv_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<[f32; 2]>>> {
+        let sample = sample.into();
+        if let Some(uv) = &self.uv {
+            let array = uv.load_expanded_sample(sample, reader)?;
+            let array = if let PodArray::F32(array) = array {
+                chunk_vector_by_2(array)?
+            } else {
+                return Err(InternalError::Unreachable.into());
+            };
+            expand_to_face_varying(
+                array,
+                uv.scope(),
+                || self.load_face_counts_sample(sample, reader),
+                || self.load_face_indices_sample(sample, reader),
+            )
+            .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn load_velocities_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<Vec<[f32; 3]>>> {
+        if let Some(velocities) = &self.velocities {
+            let array = velocities.load_sample(sample, reader)?;
+            if let PodArray::F32(array) = array {
+                Ok(Some(chunk_vector_by_3(array)?))
+            } else {
+                Err(InternalError::Unreachable.into())
+            }
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+// Settings that are not stored use their default value of 0.
+fn load_i32_setting(
+    property: &Option<ScalarPropertyReader>,
+    sample: impl Into<SampleSelector>,
+    reader: &dyn ArchiveReader,
+) -> Result<i32> {
+    let property = if let Some(property) = property {
+        property
+    } else {
+        return Ok(0);
+    };
+
+    load_i32_scalar_sample(property, sample, reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::archive_writer::ArchiveWriter;
+    use crate::metadata::MetaData;
+    use crate::object_writer::ObjectWriter;
+    use crate::schemas::tests::{array_property, scalar_property, schema_object, TestArchive};
+    use std::io::Cursor;
+
+    // A quad with only the required properties and the given settings.
+    fn write_subd(
+        settings: impl FnOnce(&mut TestArchive) -> Result<Vec<PropertyWriter>>,
+    ) -> Result<Vec<u8>> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let positions = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let mut properties = vec![
+            scalar_property(
+                ".selfBnds",
+                BOX_TYPE,
+                MetaData::default(),
+                0,
+                &[BoundingBox::from_points(&positions).to_pod_array()],
+                &mut archive,
+            )?,
+            array_property(
+                "P",
+                F32X3_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::F32(positions.iter().flatten().copied().collect())],
+                &mut archive,
+            )?,
+            array_property(
+                ".faceIndices",
+                I32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::I32(vec![0, 1, 2, 3])],
+                &mut archive,
+            )?,
+            array_property(
+                ".faceCounts",
+                I32_TYPE,
+                MetaData::default(),
+                0,
+                &[PodArray::I32(vec![4])],
+                &mut archive,
+            )?,
+        ];
+        properties.extend(settings(&mut archive)?);
+
+        let mut root = ObjectWriter::new_root();
+        root.add_child(schema_object("subd", "AbcGeom_SubD_v1", properties)?)?;
+        Ok(archive.finish(root)?.into_inner())
+    }
+
+    fn load_subd(data: &Vec<u8>) -> Result<SubDSchema> {
+        let archive = Archive::new(data)?;
+        let root = archive.load_root_object(data)?;
+        let object =
+            root.load_child(0, data, &archive.indexed_meta_data, &archive.time_samplings)?;
+        SubDSchema::new_from_object_reader(&object, data, &archive)
+    }
+
+    #[test]
+    fn unset_settings_use_their_defaults() -> Result<()> {
+        let data = write_subd(|_| Ok(vec![]))?;
+        let subd = load_subd(&data)?;
+
+        assert_eq!(
+            subd.load_subdivision_scheme_sample(0, &data)?,
+            "catmull-clark"
+        );
+        assert_eq!(
+            subd.load_face_varying_interpolate_boundary_sample(0, &data)?,
+            0
+        );
+        assert_eq!(
+            subd.load_face_varying_propagate_corners_sample(0, &data)?,
+            0
+        );
+        assert_eq!(subd.load_interpolate_boundary_sample(0, &data)?, 0);
+        assert!(!subd.has_creases() && !subd.has_corners() && !subd.has_holes());
+        assert_eq!(subd.load_crease_indices_sample(0, &data)?, None);
+        assert_eq!(subd.load_corner_sharpnesses_sample(0, &data)?, None);
+        assert_eq!(subd.load_holes_sample(0, &data)?, None);
+        assert!(!subd.has_uv() && !subd.has_velocities());
+        assert!(subd.face_sets().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn stored_settings() -> Result<()> {
+        let data = write_subd(|archive| {
+            Ok(vec![
+                scalar_property(
+                    ".scheme",
+                    STRING_TYPE,
+                    MetaData::default(),
+                    0,
+                    &[PodArray::String(vec!["loop".to_owned()])],
+                    archive,
+                )?,
+                scalar_property(
+                    ".interpolateBoundary",
+                    I32_TYPE,
+                    MetaData::default(),
+                    0,
+                    &[PodArray::I32(vec![1])],
+                    archive,
+                )?,
+                array_property(
+                    ".holes",
+                    I32_TYPE,
+                    MetaData::default(),
+                    0,
+                    &[PodArray::I32(vec![0])],
+                    archive,
+                )?,
+            ])
+        })?;
+        let subd = load_subd(&data)?;
+
+        assert_eq!(subd.load_subdivision_scheme_sample(0, &data)?, "loop");
+        assert_eq!(subd.load_interpolate_boundary_sample(0, &data)?, 1);
+        assert_eq!(
+            subd.load_face_varying_interpolate_boundary_sample(0, &data)?,
+            0
+        );
+        assert!(subd.has_holes());
+        assert_eq!(subd.load_holes_sample(0, &data)?, Some(vec![0]));
+
+        Ok(())
+    }
+}
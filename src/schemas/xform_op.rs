@@ -0,0 +1,584 @@
+use crate::result::*;
+use std::convert::TryFrom;
+
+pub(crate) const IDENTITY_MATRIX: [[f64; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+// Matrices are applied to row vectors, so `a * b` first transforms by `a` and then by `b`.
+pub(crate) fn multiply_matrices(a: &[[f64; 4]; 4], b: &[[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    let mut result = [[0.0; 4]; 4];
+    for (row, result_row) in result.iter_mut().enumerate() {
+        for (column, value) in result_row.iter_mut().enumerate() {
+            *value = (0..4).map(|i| a[row][i] * b[i][column]).sum();
+        }
+    }
+    result
+}
+
+// Builds the matrix of a whole stack of ops, the last op is the first to be applied.
+pub(crate) fn concatenate_ops(ops: &[XformOp]) -> [[f64; 4]; 4] {
+    ops.iter().fold(IDENTITY_MATRIX, |matrix, op| {
+        multiply_matrices(&op.to_matrix(), &matrix)
+    })
+}
+
+// Blends two matrices by decomposing them into scale, rotation and translation, shear is not
+// preserved.
+pub(crate) fn interpolate_matrices(
+    a: &[[f64; 4]; 4],
+    b: &[[f64; 4]; 4],
+    alpha: f64,
+) -> [[f64; 4]; 4] {
+    let (a_scale, a_rotation, a_translation) = decompose_matrix(a);
+    let (b_scale, b_rotation, b_translation) = decompose_matrix(b);
+
+    let lerp = |a: [f64; 3], b: [f64; 3]| -> [f64; 3] {
+        std::array::from_fn(|i| a[i] + (b[i] - a[i]) * alpha)
+    };
+    compose_matrix(
+        lerp(a_scale, b_scale),
+        slerp(a_rotation, b_rotation, alpha),
+        lerp(a_translation, b_translation),
+    )
+}
+
+// Splits a matrix into a scale, a rotation quaternion stored as [x, y, z, w] and a translation.
+fn decompose_matrix(matrix: &[[f64; 4]; 4]) -> ([f64; 3], [f64; 4], [f64; 3]) {
+    let mut scale: [f64; 3] = std::array::from_fn(|row| {
+        let [x, y, z, _] = matrix[row];
+        (x * x + y * y + z * z).sqrt()
+    });
+
+    // a mirrored matrix is represented by a negative scale on the first axis
+    let determinant = matrix[0][0] * (matrix[1][1] * matrix[2][2] - matrix[1][2] * matrix[2][1])
+        - matrix[0][1] * (matrix[1][0] * matrix[2][2] - matrix[1][2] * matrix[2][0])
+        + matrix[0][2] * (matrix[1][0] * matrix[2][1] - matrix[1][1] * matrix[2][0]);
+    if determinant < 0.0 {
+        scale[0] = -scale[0];
+    }
+
+    // the rotation is transposed, so that the usual column vector formulas apply
+    let rotation = |row: usize, column: usize| {
+        if scale[column] == 0.0 {
+            if row == column {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            matrix[column][row] / scale[column]
+        }
+    };
+
+    let trace = rotation(0, 0) + rotation(1, 1) + rotation(2, 2);
+    let quaternion = if trace > 0.0 {
+        let s = (trace + 1.0).sqrt() * 2.0;
+        [
+            (rotation(2, 1) - rotation(1, 2)) / s,
+            (rotation(0, 2) - rotation(2, 0)) / s,
+            (rotation(1, 0) - rotation(0, 1)) / s,
+            0.25 * s,
+        ]
+    } else if rotation(0, 0) > rotation(1, 1) && rotation(0, 0) > rotation(2, 2) {
+        let s = (1.0 + rotation(0, 0) - rotation(1, 1) - rotation(2, 2)).sqrt() * 2.0;
+        [
+            0.25 * s,
+            (rotation(0, 1) + rotation(1, 0)) / s,
+            (rotation(0, 2) + rotation(2, 0)) / s,
+            (rotation(2, 1) - rotation(1, 2)) / s,
+        ]
+    } else if rotation(1, 1) > rotation(2, 2) {
+        let s = (1.0 + rotation(1, 1) - rotation(0, 0) - rotation(2, 2)).sqrt() * 2.0;
+        [
+            (rotation(0, 1) + rotation(1, 0)) / s,
+            0.25 * s,
+            (rotation(1, 2) + rotation(2, 1)) / s,
+            (rotation(0, 2) - rotation(2, 0)) / s,
+        ]
+    } else {
+        let s = (1.0 + rotation(2, 2) - rotation(0, 0) - rotation(1, 1)).sqrt() * 2.0;
+        [
+            (rotation(0, 2) + rotation(2, 0)) / s,
+            (rotation(1, 2) + rotation(2, 1)) / s,
+            0.25 * s,
+            (rotation(1, 0) - rotation(0, 1)) / s,
+        ]
+    };
+
+    let translation = [matrix[3][0], matrix[3][1], matrix[3][2]];
+    (scale, quaternion, translation)
+}
+
+fn compose_matrix(scale: [f64; 3], quaternion: [f64; 4], translation: [f64; 3]) -> [[f64; 4]; 4] {
+    let [x, y, z, w] = quaternion;
+    let rotation = [
+        [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y + z * w),
+            2.0 * (x * z - y * w),
+        ],
+        [
+            2.0 * (x * y - z * w),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z + x * w),
+        ],
+        [
+            2.0 * (x * z + y * w),
+            2.0 * (y * z - x * w),
+            1.0 - 2.0 * (x * x + y * y),
+        ],
+    ];
+
+    let mut matrix = IDENTITY_MATRIX;
+    for (row, rotation_row) in rotation.iter().enumerate() {
+        for (column, value) in rotation_row.iter().enumerate() {
+            matrix[row][column] = value * scale[row];
+        }
+    }
+    matrix[3][..3].copy_from_slice(&translation);
+    matrix
+}
+
+fn slerp(a: [f64; 4], mut b: [f64; 4], alpha: f64) -> [f64; 4] {
+    let mut dot = (0..4).map(|i| a[i] * b[i]).sum::<f64>();
+    // take the shortest path between the two rotations
+    if dot < 0.0 {
+        b = b.map(|x| -x);
+        dot = -dot;
+    }
+
+    let (a_weight, b_weight) = if dot > 0.9995 {
+        (1.0 - alpha, alpha)
+    } else {
+        let angle = dot.acos();
+        let sin = angle.sin();
+        (
+            ((1.0 - alpha) * angle).sin() / sin,
+            (alpha * angle).sin() / sin,
+        )
+    };
+
+    let quaternion: [f64; 4] = std::array::from_fn(|i| a[i] * a_weight + b[i] * b_weight);
+    let length = quaternion.iter().map(|x| x * x).sum::<f64>().sqrt();
+    quaternion.map(|x| x / length)
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XformOperationType {
+    Scale = 0,
+    Translate,
+    Rotate,
+    Matrix,
+    RotateX,
+    RotateY,
+    RotateZ,
+}
+
+impl XformOperationType {
+    pub fn channel_count(self) -> usize {
+        match self {
+            XformOperationType::Scale => 3,
+            XformOperationType::Translate => 3,
+            XformOperationType::Rotate => 4,
+            XformOperationType::Matrix => 16,
+            XformOperationType::RotateX => 1,
+            XformOperationType::RotateY => 1,
+            XformOperationType::RotateZ => 1,
+        }
+    }
+
+    fn default_channel_value(self, index: usize) -> f64 {
+        match self {
+            XformOperationType::Scale => 1.0,
+            XformOperationType::Matrix if matches!(index, 0 | 5 | 10 | 15) => 1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+impl TryFrom<u8> for XformOperationType {
+    type Error = OgawaError;
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            x if x == XformOperationType::Scale as u8 => Ok(XformOperationType::Scale),
+            x if x == XformOperationType::Translate as u8 => Ok(XformOperationType::Translate),
+            x if x == XformOperationType::Rotate as u8 => Ok(XformOperationType::Rotate),
+            x if x == XformOperationType::Matrix as u8 => Ok(XformOperationType::Matrix),
+            x if x == XformOperationType::RotateX as u8 => Ok(XformOperationType::RotateX),
+            x if x == XformOperationType::RotateY as u8 => Ok(XformOperationType::RotateY),
+            x if x == XformOperationType::RotateZ as u8 => Ok(XformOperationType::RotateZ),
+            _ => Err(ParsingError::UnsupportedAlembicFile.into()),
+        }
+    }
+}
+
+// A single operation of a transform stack. Rotations are in degrees and matrices are stored
+// row by row, with the translation in the last row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XformOp {
+    pub operation_type: XformOperationType,
+    // Describes what the operation represents to DCCs, like a pivot or a shear, it does not
+    // change how the operation is evaluated.
+    pub hint: u8,
+    pub channels: Vec<f64>,
+}
+
+impl XformOp {
+    pub fn new(operation_type: XformOperationType, hint: u8) -> Self {
+        Self {
+            operation_type,
+            hint,
+            channels: (0..operation_type.channel_count())
+                .map(|index| operation_type.default_channel_value(index))
+                .collect(),
+        }
+    }
+
+    pub fn translate(translation: [f64; 3]) -> Self {
+        Self {
+            operation_type: XformOperationType::Translate,
+            hint: 0,
+            channels: translation.to_vec(),
+        }
+    }
+
+    pub fn scale(scale: [f64; 3]) -> Self {
+        Self {
+            operation_type: XformOperationType::Scale,
+            hint: 0,
+            channels: scale.to_vec(),
+        }
+    }
+
+    pub fn rotate(axis: [f64; 3], angle: f64) -> Self {
+        Self {
+            operation_type: XformOperationType::Rotate,
+            hint: 0,
+            channels: vec![axis[0], axis[1], axis[2], angle],
+        }
+    }
+
+    pub fn rotate_x(angle: f64) -> Self {
+        Self {
+            operation_type: XformOperationType::RotateX,
+            hint: 0,
+            channels: vec![angle],
+        }
+    }
+
+    pub fn rotate_y(angle: f64) -> Self {
+        Self {
+            operation_type: XformOperationType::RotateY,
+            hint: 0,
+            channels: vec![angle],
+        }
+    }
+
+    pub fn rotate_z(angle: f64) -> Self {
+        Self {
+            operation_type: XformOperationType::RotateZ,
+            hint: 0,
+            channels: vec![angle],
+        }
+    }
+
+    pub fn matrix(matrix: [[f64; 4]; 4]) -> Self {
+        Self {
+            operation_type: XformOperationType::Matrix,
+            hint: 0,
+            channels: matrix.iter().flatten().copied().collect(),
+        }
+    }
+
+    pub fn with_hint(mut self, hint: u8) -> Self {
+        self.hint = hint;
+        self
+    }
+
+    pub fn is_identity(&self) -> bool {
+        match self.operation_type {
+            // any axis is fine as long as there is no rotation around it
+            XformOperationType::Rotate => self.channels.get(3).copied().unwrap_or(0.0) == 0.0,
+            operation_type => self
+                .channels
+                .iter()
+                .enumerate()
+                .all(|(index, &value)| value == operation_type.default_channel_value(index)),
+        }
+    }
+
+    pub fn to_matrix(&self) -> [[f64; 4]; 4] {
+        // missing channels fall back to the values that leave the transform unchanged
+        let channel = |index: usize| {
+            self.channels
+                .get(index)
+                .copied()
+                .unwrap_or_else(|| self.operation_type.default_channel_value(index))
+        };
+
+        let mut matrix = IDENTITY_MATRIX;
+        match self.operation_type {
+            XformOperationType::Scale => {
+                for (axis, row) in matrix.iter_mut().take(3).enumerate() {
+                    row[axis] = channel(axis);
+                }
+            }
+            XformOperationType::Translate => {
+                for (axis, value) in matrix[3].iter_mut().take(3).enumerate() {
+                    *value = channel(axis);
+                }
+            }
+            XformOperationType::Rotate => {
+                matrix = axis_angle_matrix([channel(0), channel(1), channel(2)], channel(3))
+            }
+            XformOperationType::RotateX => matrix = axis_angle_matrix([1.0, 0.0, 0.0], channel(0)),
+            XformOperationType::RotateY => matrix = axis_angle_matrix([0.0, 1.0, 0.0], channel(0)),
+            XformOperationType::RotateZ => matrix = axis_angle_matrix([0.0, 0.0, 1.0], channel(0)),
+            XformOperationType::Matrix => {
+                for (index, value) in matrix.iter_mut().flatten().enumerate() {
+                    *value = channel(index);
+                }
+            }
+        }
+        matrix
+    }
+
+    // Ops are stored as a single byte, the operation type in the high and the hint in the low
+    // four bits.
+    pub(crate) fn encode(&self) -> u8 {
+        ((self.operation_type as u8) << 4) | (self.hint & 0xf)
+    }
+
+    pub(crate) fn decode(op: u8) -> Result<Self> {
+        Ok(Self::new(XformOperationType::try_from(op >> 4)?, op & 0xf))
+    }
+}
+
+fn axis_angle_matrix(axis: [f64; 3], degrees: f64) -> [[f64; 4]; 4] {
+    let length = (axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]).sqrt();
+    if length == 0.0 {
+        return IDENTITY_MATRIX;
+    }
+    let [x, y, z] = axis.map(|v| v / length);
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let t = 1.0 - cos;
+
+    [
+        [
+            x * x * t + cos,
+            x * y * t + z * sin,
+            x * z * t - y * sin,
+            0.0,
+        ],
+        [
+            x * y * t - z * sin,
+            y * y * t + cos,
+            y * z * t + x * sin,
+            0.0,
+        ],
+        [
+            x * z * t + y * sin,
+            y * z * t - x * sin,
+            z * z * t + cos,
+            0.0,
+        ],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_matrix_eq(a: [[f64; 4]; 4], b: [[f64; 4]; 4]) {
+        for (a, b) in a.iter().flatten().zip(b.iter().flatten()) {
+            assert!((a - b).abs() < 1e-12, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn ops_are_encoded_with_their_hint() -> Result<()> {
+        assert_eq!(XformOp::scale([1.0; 3]).encode(), 0x00);
+        assert_eq!(XformOp::translate([0.0; 3]).with_hint(3).encode(), 0x13);
+        assert_eq!(XformOp::rotate_z(0.0).with_hint(1).encode(), 0x61);
+
+        let op = XformOp::decode(0x32)?;
+        assert_eq!(op.operation_type, XformOperationType::Matrix);
+        assert_eq!(op.hint, 2);
+        assert_matrix_eq(op.to_matrix(), IDENTITY_MATRIX);
+        assert!(op.is_identity());
+
+        assert!(XformOp::decode(0x70).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn op_matrices() {
+        assert_matrix_eq(
+            XformOp::translate([1.0, 2.0, 3.0]).to_matrix(),
+            [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [1.0, 2.0, 3.0, 1.0],
+            ],
+        );
+        assert_matrix_eq(
+            XformOp::scale([2.0, 3.0, 4.0]).to_matrix(),
+            [
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 3.0, 0.0, 0.0],
+                [0.0, 0.0, 4.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        );
+
+        // rotating x by 90 degrees around y points it along -z
+        let rotate_y = [
+            [0.0, 0.0, -1.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        assert_matrix_eq(XformOp::rotate_y(90.0).to_matrix(), rotate_y);
+        assert_matrix_eq(XformOp::rotate([0.0, 2.0, 0.0], 90.0).to_matrix(), rotate_y);
+        assert_matrix_eq(
+            XformOp::rotate_x(90.0).to_matrix(),
+            [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, -1.0, 0.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        );
+        assert_matrix_eq(
+            XformOp::rotate_z(90.0).to_matrix(),
+            [
+                [0.0, 1.0, 0.0, 0.0],
+                [-1.0, 0.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        );
+
+        let matrix = [
+            [1.0, 2.0, 3.0, 0.0],
+            [4.0, 5.0, 6.0, 0.0],
+            [7.0, 8.0, 9.0, 0.0],
+            [10.0, 11.0, 12.0, 1.0],
+        ];
+        assert_matrix_eq(XformOp::matrix(matrix).to_matrix(), matrix);
+    }
+
+    #[test]
+    fn op_stacks_apply_the_last_op_first() {
+        // the point is rotated first and translated afterwards
+        assert_matrix_eq(
+            concatenate_ops(&[XformOp::translate([1.0, 2.0, 3.0]), XformOp::rotate_y(90.0)]),
+            [
+                [0.0, 0.0, -1.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0, 0.0],
+                [1.0, 2.0, 3.0, 1.0],
+            ],
+        );
+
+        // the translation is scaled as well when it is applied before the scale
+        assert_matrix_eq(
+            concatenate_ops(&[
+                XformOp::scale([2.0, 3.0, 4.0]),
+                XformOp::translate([1.0, 2.0, 3.0]),
+            ]),
+            [
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 3.0, 0.0, 0.0],
+                [0.0, 0.0, 4.0, 0.0],
+                [2.0, 6.0, 12.0, 1.0],
+            ],
+        );
+        assert_matrix_eq(
+            concatenate_ops(&[
+                XformOp::translate([1.0, 2.0, 3.0]),
+                XformOp::scale([2.0, 3.0, 4.0]),
+            ]),
+            [
+                [2.0, 0.0, 0.0, 0.0],
+                [0.0, 3.0, 0.0, 0.0],
+                [0.0, 0.0, 4.0, 0.0],
+                [1.0, 2.0, 3.0, 1.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn trs_matrices_round_trip() {
+        for scale in [[2.0, 3.0, 4.0], [-2.0, 3.0, 4.0]] {
+            let matrix = concatenate_ops(&[
+                XformOp::translate([1.0, 2.0, 3.0]),
+                XformOp::rotate([1.0, 1.0, 0.0], 30.0),
+                XformOp::scale(scale),
+            ]);
+
+            let (decomposed_scale, quaternion, translation) = decompose_matrix(&matrix);
+            assert_eq!(translation, [1.0, 2.0, 3.0]);
+            for (a, b) in decomposed_scale.iter().zip(scale) {
+                assert!((a - b).abs() < 1e-12, "{:?} != {:?}", a, b);
+            }
+            assert_matrix_eq(
+                compose_matrix(decomposed_scale, quaternion, translation),
+                matrix,
+            );
+        }
+    }
+
+    #[test]
+    fn slerp_midpoint() {
+        let half_sqrt = 0.5f64.sqrt();
+        let identity = [0.0, 0.0, 0.0, 1.0];
+        let rotate_y = [0.0, half_sqrt, 0.0, half_sqrt];
+
+        let quaternion = slerp(identity, rotate_y, 0.5);
+        let expected = [
+            0.0,
+            22.5f64.to_radians().sin(),
+            0.0,
+            22.5f64.to_radians().cos(),
+        ];
+        for (a, b) in quaternion.iter().zip(expected) {
+            assert!((a - b).abs() < 1e-12, "{:?} != {:?}", a, b);
+        }
+
+        // the negated quaternion is the same rotation and takes the same path
+        let quaternion = slerp(identity, rotate_y.map(|x| -x), 0.5);
+        for (a, b) in quaternion.iter().zip(expected) {
+            assert!((a - b).abs() < 1e-12, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn interpolated_matrices() {
+        let a = concatenate_ops(&[XformOp::scale([1.0; 3])]);
+        let b = concatenate_ops(&[
+            XformOp::translate([2.0, 4.0, 0.0]),
+            XformOp::rotate_y(90.0),
+            XformOp::scale([3.0; 3]),
+        ]);
+
+        assert_matrix_eq(interpolate_matrices(&a, &b, 0.0), a);
+        assert_matrix_eq(interpolate_matrices(&a, &b, 1.0), b);
+        assert_matrix_eq(
+            interpolate_matrices(&a, &b, 0.5),
+            concatenate_ops(&[
+                XformOp::translate([1.0, 2.0, 0.0]),
+                XformOp::rotate_y(45.0),
+                XformOp::scale([2.0; 3]),
+            ]),
+        );
+    }
+}
@@ -1,15 +1,25 @@
+use super::geom_param::GeomParam;
+use super::xform_op::{
+    concatenate_ops, interpolate_matrices, multiply_matrices, XformOp, IDENTITY_MATRIX,
+};
+use super::XFORM_SCHEMA;
 use crate::object_reader::ObjectReader;
 use crate::pod::*;
 use crate::property::*;
 use crate::reader::ArchiveReader;
 use crate::result::*;
+use crate::time_sampling::{SampleSelector, TimeSampling};
 use crate::Archive;
 
 #[derive(Debug)]
 pub struct XformSchema {
     pub child_bounds: Option<ScalarPropertyReader>,
-    pub arb_geometry_parameters: Option<CompoundPropertyReader>,
+    arb_geometry_parameters: Option<CompoundPropertyReader>,
     pub user_properties: Option<CompoundPropertyReader>,
+    pub inherits: Option<ScalarPropertyReader>,
+    pub ops: Option<ScalarPropertyReader>,
+    pub vals: Option<PropertyReader>,
+    pub anim_channels: Option<ArrayPropertyReader>,
     pub is_constant_identity: bool,
     pub is_constant: bool,
 }
@@ -17,7 +27,7 @@ pub struct XformSchema {
 impl XformSchema {
     pub fn new_from_object_reader(
         object: &ObjectReader,
-        reader: &mut dyn ArchiveReader,
+        reader: &dyn ArchiveReader,
         archive: &Archive,
     ) -> Result<Self> {
         let properties = object
@@ -37,22 +47,40 @@ impl XformSchema {
             .map(|x| x.try_into())
             .transpose()?;
 
+        // the channels are stored in a scalar property, unless there are too many to fit in
+        // its extent
         let vals = properties
             .load_sub_property_by_name(".vals", reader, archive)?
             .map(|x| {
-                let _data_type = match &x {
+                let data_type = match &x {
                     PropertyReader::Array(r) => &r.header.data_type,
                     PropertyReader::Scalar(r) => &r.header.data_type,
                     _ => return Err(ParsingError::IncompatibleSchema),
                 };
-
-                //TODO(max): What data type should we check for?
-                //if data_type.pod_type != PodType::
+                if data_type.pod_type != PodType::F64 {
+                    return Err(ParsingError::IncompatibleSchema);
+                }
 
                 Ok(x)
             })
             .transpose()?;
 
+        let ops: Option<ScalarPropertyReader> = properties
+            .load_sub_property_by_name(".ops", reader, archive)?
+            .map(|x| x.try_into())
+            .transpose()?;
+        if ops
+            .as_ref()
+            .is_some_and(|ops| ops.header.data_type.pod_type != PodType::U8)
+        {
+            return Err(ParsingError::IncompatibleSchema.into());
+        }
+
+        let anim_channels = properties
+            .load_sub_property_by_name_checked(".animChans", reader, archive, Some(&U32_TYPE))?
+            .map(|x| x.try_into())
+            .transpose()?;
+
         let is_constant_identity = properties
             .find_sub_property_index("isNotConstantIdentity")
             .is_none();
@@ -74,37 +102,6 @@ impl XformSchema {
                 true
             };
 
-        // TODO(max): Animation channels
-        /*
-        let mut anim_channels = std::collections::BTreeSet::<u32>::new();
-        let anim_channels_prop = properties
-            .load_sub_property_by_name(
-                ".animChans",
-                reader,
-                &archive.indexed_meta_data,
-                &archive.time_samplings,
-            )?
-            .map(|x| {
-                let x: ArrayPropertyReader = x.try_into()?;
-                if x.header.data_type == BOX_TYPE {
-                    Ok(x)
-                } else {
-                    Err(ParsingError::IncompatibleSchema)
-                }
-            })
-            .transpose()?;
-        if let Some(anim_channels_prop) = anim_channels_prop {
-            let sample_count = anim_channels_prop.sample_count();
-            if sample_count > 0 {
-                for i in 0..sample_count {
-
-                }
-            }
-        }
-        */
-
-        // TODO(max): ops
-
         let arb_geometry_parameters = properties
             .load_sub_property_by_name(".arbGeomParams", reader, archive)?
             .map(|x| x.try_into())
@@ -121,6 +118,10 @@ impl XformSchema {
             is_constant,
             arb_geometry_parameters,
             user_properties,
+            inherits,
+            ops,
+            vals,
+            anim_channels,
         })
     }
 
@@ -130,4 +131,609 @@ impl XformSchema {
     pub fn is_constant_identity(&self) -> bool {
         self.is_constant_identity
     }
+
+    pub fn sample_count(&self) -> u32 {
+        let vals_sample_count = match &self.vals {
+            Some(PropertyReader::Array(r)) => r.sample_count(),
+            Some(PropertyReader::Scalar(r)) => r.sample_count(),
+            _ => 0,
+        };
+        let inherits_sample_count = self.inherits.as_ref().map_or(0, |x| x.sample_count());
+        vals_sample_count.max(inherits_sample_count)
+    }
+
+    pub fn load_child_bounds_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Option<BoundingBox>> {
+        let child_bounds = if let Some(child_bounds) = &self.child_bounds {
+            child_bounds
+        } else {
+            return Ok(None);
+        };
+
+        let pod_array = child_bounds.load_sample(sample, reader)?;
+        BoundingBox::from_pod_array(pod_array).map(Some)
+    }
+
+    pub fn load_arb_geometry_parameters(
+        &self,
+        reader: &dyn ArchiveReader,
+        archive: &Archive,
+    ) -> Result<Vec<GeomParam>> {
+        GeomParam::load_all_optional(self.arb_geometry_parameters.as_ref(), reader, archive)
+    }
+
+    // Returns the index of the last sample at or before `time`.
+    pub fn floor_sample_index(&self, time: f64) -> u32 {
+        self.time_sampling().floor_index(time, self.sample_count())
+    }
+
+    // The channels and the inherits flag are written together, so they share their sampling.
+    fn time_sampling(&self) -> TimeSampling {
+        let header = match (&self.vals, &self.inherits) {
+            (Some(PropertyReader::Array(r)), _) => &r.header,
+            (Some(PropertyReader::Scalar(r)), _) => &r.header,
+            (_, Some(r)) => &r.header,
+            _ => return TimeSampling::default(),
+        };
+
+        header.time_sampling.as_deref().cloned().unwrap_or_default()
+    }
+
+    // Returns the stack of operations with their default channel values, the stack is the same
+    // for every sample.
+    pub fn load_ops(&self, reader: &dyn ArchiveReader) -> Result<Vec<XformOp>> {
+        let ops = if let Some(ops) = &self.ops {
+            ops
+        } else {
+            return Ok(vec![]);
+        };
+
+        let pod_array = ops.load_sample(0, reader)?;
+        let pod_array = if let PodArray::U8(array) = pod_array {
+            array
+        } else {
+            return Err(InternalError::Unreachable.into());
+        };
+
+        pod_array.into_iter().map(XformOp::decode).collect()
+    }
+
+    pub fn load_ops_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<Vec<XformOp>> {
+        let sample = sample.into();
+        let mut ops = self.load_ops(reader)?;
+        if ops.is_empty() {
+            return Ok(ops);
+        }
+
+        let pod_array = match &self.vals {
+            Some(PropertyReader::Array(r)) => r.load_sample(sample, reader)?,
+            Some(PropertyReader::Scalar(r)) => r.load_sample(sample, reader)?,
+            _ => return Err(ParsingError::IncompatibleSchema.into()),
+        };
+        let pod_array = if let PodArray::F64(array) = pod_array {
+            array
+        } else {
+            return Err(InternalError::Unreachable.into());
+        };
+
+        let channel_count = ops.iter().map(|op| op.channels.len()).sum::<usize>();
+        if pod_array.len() != channel_count {
+            return Err(ParsingError::InvalidAlembicFile.into());
+        }
+
+        let mut values = pod_array.into_iter();
+        for op in &mut ops {
+            for (channel, value) in op.channels.iter_mut().zip(&mut values) {
+                *channel = value;
+            }
+        }
+
+        Ok(ops)
+    }
+
+    pub fn load_matrix_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<[[f64; 4]; 4]> {
+        let ops = self.load_ops_sample(sample, reader)?;
+        Ok(concatenate_ops(&ops))
+    }
+
+    // Loads the matrix at `time`, blending the samples around it. Matrices are decomposed so that
+    // rotations are interpolated along the shortest arc.
+    pub fn load_interpolated_matrix(
+        &self,
+        time: f64,
+        reader: &dyn ArchiveReader,
+    ) -> Result<[[f64; 4]; 4]> {
+        let sample_count = self.sample_count();
+        if sample_count == 0 {
+            return Ok(IDENTITY_MATRIX);
+        }
+
+        let time_sampling = self.time_sampling();
+        let floor_index = time_sampling.floor_index(time, sample_count);
+        let ceil_index = time_sampling.ceil_index(time, sample_count);
+        let floor_matrix = self.load_matrix_sample(floor_index, reader)?;
+        let floor_time = time_sampling.sample_time(floor_index);
+        let ceil_time = time_sampling.sample_time(ceil_index);
+        if floor_index == ceil_index || self.is_constant || ceil_time <= floor_time {
+            return Ok(floor_matrix);
+        }
+
+        let ceil_matrix = self.load_matrix_sample(ceil_index, reader)?;
+        let alpha = (time - floor_time) / (ceil_time - floor_time);
+        Ok(interpolate_matrices(&floor_matrix, &ceil_matrix, alpha))
+    }
+
+    pub fn load_inherits_sample(
+        &self,
+        sample: impl Into<SampleSelector>,
+        reader: &dyn ArchiveReader,
+    ) -> Result<bool> {
+        let inherits = if let Some(inherits) = &self.inherits {
+            inherits
+        } else {
+            return Ok(true);
+        };
+
+        let pod_array = inherits.load_sample(sample, reader)?;
+        if let PodArray::Boolean(array) = pod_array {
+            Ok(array.first().copied().unwrap_or(true))
+        } else {
+            Err(InternalError::Unreachable.into())
+        }
+    }
+
+    // Indices of the channels that change over time.
+    pub fn load_animated_channels(&self, reader: &dyn ArchiveReader) -> Result<Vec<u32>> {
+        let anim_channels = if let Some(anim_channels) = &self.anim_channels {
+            anim_channels
+        } else {
+            return Ok(vec![]);
+        };
+
+        let pod_array = anim_channels.load_sample(0, reader)?;
+        if let PodArray::U32(array) = pod_array {
+            Ok(array)
+        } else {
+            Err(InternalError::Unreachable.into())
+        }
+    }
+}
+
+// Walks from the root down to the object at `full_name`, concatenating the matrices of all
+// xforms along the way. An xform that does not inherit discards the transforms of its parents.
+pub(crate) fn load_world_matrix(
+    archive: &Archive,
+    full_name: &str,
+    time: f64,
+    reader: &dyn ArchiveReader,
+) -> Result<[[f64; 4]; 4]> {
+    let mut object = archive.load_root_object(reader)?;
+    let mut matrix = IDENTITY_MATRIX;
+
+    for name in full_name.split('/').filter(|name| !name.is_empty()) {
+        let index = *object
+            .child_map
+            .get(name)
+            .ok_or(UserError::InvalidParameter)?;
+        object = object.load_child(
+            index,
+            reader,
+            &archive.indexed_meta_data,
+            &archive.time_samplings,
+        )?;
+        matrix = apply_object_matrix(archive, &object, &matrix, time, reader)?;
+    }
+
+    Ok(matrix)
+}
+
+// Returns the world space bounds of the object at `full_name` and everything below it. Child
+// bounds stored on an object are used instead of visiting its children.
+pub(crate) fn load_hierarchy_bounds(
+    archive: &Archive,
+    full_name: &str,
+    time: f64,
+    reader: &dyn ArchiveReader,
+) -> Result<BoundingBox> {
+    let parent_name = full_name
+        .trim_end_matches('/')
+        .rsplit_once('/')
+        .map_or("", |(parent_name, _)| parent_name);
+    let parent_matrix = load_world_matrix(archive, parent_name, time, reader)?;
+
+    let object = archive.load_object(full_name, reader)?;
+    load_object_bounds(archive, &object, &parent_matrix, time, reader)
+}
+
+fn load_object_bounds(
+    archive: &Archive,
+    object: &ObjectReader,
+    parent_matrix: &[[f64; 4]; 4],
+    time: f64,
+    reader: &dyn ArchiveReader,
+) -> Result<BoundingBox> {
+    let matrix = apply_object_matrix(archive, object, parent_matrix, time, reader)?;
+
+    let mut bounds = BoundingBox::empty();
+    let mut child_bounds = None;
+    if let Some(properties) = object.properties() {
+        if properties.sub_property_count() > 0 {
+            if let PropertyReader::Compound(properties) =
+                properties.load_sub_property(0, reader, archive)?
+            {
+                if let Some(self_bounds) =
+                    load_bounds_at(&properties, ".selfBnds", time, reader, archive)?
+                {
+                    bounds = bounds.union(&self_bounds.transform(&matrix));
+                }
+                child_bounds = load_bounds_at(&properties, ".childBnds", time, reader, archive)?;
+            }
+        }
+    }
+
+    match child_bounds {
+        Some(child_bounds) if !child_bounds.is_empty() => {
+            bounds = bounds.union(&child_bounds.transform(&matrix));
+        }
+        _ => {
+            for index in 0..object.child_count() {
+                let child = object.load_child(
+                    index,
+                    reader,
+                    &archive.indexed_meta_data,
+                    &archive.time_samplings,
+                )?;
+                let child_bounds = load_object_bounds(archive, &child, &matrix, time, reader)?;
+                bounds = bounds.union(&child_bounds);
+            }
+        }
+    }
+
+    Ok(bounds)
+}
+
+// Objects that are not xforms do not change the matrix of their children.
+fn apply_object_matrix(
+    archive: &Archive,
+    object: &ObjectReader,
+    parent_matrix: &[[f64; 4]; 4],
+    time: f64,
+    reader: &dyn ArchiveReader,
+) -> Result<[[f64; 4]; 4]> {
+    let schema = object.header.meta_data.tokens.get("schema");
+    if schema.map(String::as_str) != Some(XFORM_SCHEMA) {
+        return Ok(*parent_matrix);
+    }
+
+    let xform = XformSchema::new_from_object_reader(object, reader, archive)?;
+    if xform.sample_count() == 0 {
+        return Ok(*parent_matrix);
+    }
+
+    let sample_index = xform.floor_sample_index(time);
+    let local_matrix = xform.load_matrix_sample(sample_index, reader)?;
+    Ok(if xform.load_inherits_sample(sample_index, reader)? {
+        multiply_matrices(&local_matrix, parent_matrix)
+    } else {
+        local_matrix
+    })
+}
+
+fn load_bounds_at(
+    properties: &CompoundPropertyReader,
+    name: &str,
+    time: f64,
+    reader: &dyn ArchiveReader,
+    archive: &Archive,
+) -> Result<Option<BoundingBox>> {
+    let bounds = match properties.load_sub_property_by_name_checked(
+        name,
+        reader,
+        archive,
+        Some(&BOX_TYPE),
+    )? {
+        Some(PropertyReader::Scalar(bounds)) if bounds.sample_count() > 0 => bounds,
+        _ => return Ok(None),
+    };
+
+    let pod_array = bounds.load_sample(SampleSelector::Floor(time), reader)?;
+    BoundingBox::from_pod_array(pod_array).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::MetaData;
+    use crate::{
+        ArchiveWriter, ObjectWriter, PolyMeshSample, PolyMeshWriter, XformOperationType,
+        XformSample, XformWriter,
+    };
+    use std::io::Cursor;
+
+    fn assert_matrix_eq(a: [[f64; 4]; 4], b: [[f64; 4]; 4]) {
+        for (a, b) in a.iter().flatten().zip(b.iter().flatten()) {
+            assert!((a - b).abs() < 1e-12, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn animated_channels_are_collected() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut xform = XformWriter::new("xform", 0);
+        for (translation, angle) in [(0.0, 0.0), (1.0, 0.0), (1.0, 90.0)] {
+            let ops = vec![
+                XformOp::translate([translation, 0.0, 0.0]),
+                XformOp::rotate_y(angle),
+            ];
+            xform.add_sample(&XformSample::from_ops(ops), &mut archive)?;
+        }
+        let mut root = ObjectWriter::new_root();
+        root.add_child(xform.into_object_writer(&mut archive)?)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let root = archive.load_root_object(&data)?;
+        let object = root.load_child(
+            0,
+            &data,
+            &archive.indexed_meta_data,
+            &archive.time_samplings,
+        )?;
+        let xform = XformSchema::new_from_object_reader(&object, &data, &archive)?;
+        assert_eq!(xform.load_animated_channels(&data)?, [0, 3]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn matrix_samples_round_trip() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut xform = XformWriter::new("xform", 0);
+        for angle in [90.0, 180.0] {
+            let ops = vec![
+                XformOp::translate([1.0, 2.0, 3.0]),
+                XformOp::rotate_y(angle).with_hint(1),
+                XformOp::scale([2.0, 2.0, 2.0]),
+            ];
+            xform.add_sample(&XformSample::from_ops(ops), &mut archive)?;
+        }
+        let mut root = ObjectWriter::new_root();
+        root.add_child(xform.into_object_writer(&mut archive)?)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let root = archive.load_root_object(&data)?;
+        let object = root.load_child(
+            0,
+            &data,
+            &archive.indexed_meta_data,
+            &archive.time_samplings,
+        )?;
+        let xform = XformSchema::new_from_object_reader(&object, &data, &archive)?;
+
+        let ops = xform.load_ops(&data)?;
+        assert_eq!(ops.len(), 3);
+        assert_eq!(ops[1].operation_type, XformOperationType::RotateY);
+        assert_eq!(ops[1].hint, 1);
+        assert_eq!(xform.load_animated_channels(&data)?, [3]);
+        assert!(!xform.is_constant_identity());
+
+        assert_matrix_eq(
+            xform.load_matrix_sample(0, &data)?,
+            [
+                [0.0, 0.0, -2.0, 0.0],
+                [0.0, 2.0, 0.0, 0.0],
+                [2.0, 0.0, 0.0, 0.0],
+                [1.0, 2.0, 3.0, 1.0],
+            ],
+        );
+        assert_matrix_eq(
+            xform.load_matrix_sample(1, &data)?,
+            [
+                [-2.0, 0.0, 0.0, 0.0],
+                [0.0, 2.0, 0.0, 0.0],
+                [0.0, 0.0, -2.0, 0.0],
+                [1.0, 2.0, 3.0, 1.0],
+            ],
+        );
+
+        Ok(())
+    }
+
+    fn xform_object(
+        name: &str,
+        sample: XformSample,
+        archive: &mut ArchiveWriter<Cursor<Vec<u8>>>,
+    ) -> Result<ObjectWriter> {
+        let mut xform = XformWriter::new(name, 0);
+        xform.add_sample(&sample, archive)?;
+        xform.into_object_writer(archive)
+    }
+
+    #[test]
+    fn world_matrices() -> Result<()> {
+        // /a/group/b/c, where group is not an xform and c does not inherit
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let ops_a = vec![XformOp::translate([1.0, 0.0, 0.0])];
+        let ops_b = vec![XformOp::scale([2.0, 2.0, 2.0])];
+        let ops_c = vec![XformOp::translate([0.0, 1.0, 0.0])];
+
+        let c = xform_object(
+            "c",
+            XformSample {
+                ops: ops_c.clone(),
+                inherits: false,
+                child_bounds: None,
+            },
+            &mut archive,
+        )?;
+        let mut b = xform_object("b", XformSample::from_ops(ops_b.clone()), &mut archive)?;
+        b.add_child(c)?;
+        let mut group = ObjectWriter::new("group", MetaData::default());
+        group.add_child(b)?;
+        let mut a = xform_object("a", XformSample::from_ops(ops_a.clone()), &mut archive)?;
+        a.add_child(group)?;
+        let mut root = ObjectWriter::new_root();
+        root.add_child(a)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+
+        assert_matrix_eq(archive.load_world_matrix("/", 0.0, &data)?, IDENTITY_MATRIX);
+        assert_matrix_eq(
+            archive.load_world_matrix("/a", 0.0, &data)?,
+            concatenate_ops(&ops_a),
+        );
+        assert_matrix_eq(
+            archive.load_world_matrix("/a/group", 0.0, &data)?,
+            concatenate_ops(&ops_a),
+        );
+        assert_matrix_eq(
+            archive.load_world_matrix("/a/group/b", 0.0, &data)?,
+            concatenate_ops(&[ops_a, ops_b].concat()),
+        );
+        assert_matrix_eq(
+            archive.load_world_matrix("/a/group/b/c", 0.0, &data)?,
+            concatenate_ops(&ops_c),
+        );
+        assert!(matches!(
+            archive.load_world_matrix("/a/missing", 0.0, &data),
+            Err(OgawaError::UserError(UserError::InvalidParameter))
+        ));
+
+        Ok(())
+    }
+
+    fn assert_bounds_eq(bounds: BoundingBox, min: [f64; 3], max: [f64; 3]) {
+        assert_eq!((bounds.min, bounds.max), (min, max));
+    }
+
+    #[test]
+    fn hierarchy_bounds() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mesh_object = |archive: &mut ArchiveWriter<Cursor<Vec<u8>>>| -> Result<_> {
+            let mut mesh = PolyMeshWriter::new("mesh", 0);
+            let sample = PolyMeshSample {
+                positions: &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+                face_indices: &[0, 1, 2],
+                face_counts: &[3],
+                ..Default::default()
+            };
+            mesh.add_sample(&sample, archive)?;
+            mesh.into_object_writer()
+        };
+
+        // a places its mesh, b has child bounds replacing those of its mesh, and the empty
+        // child bounds of c are ignored
+        let mut root = ObjectWriter::new_root();
+        for (name, ops, child_bounds) in [
+            ("a", vec![XformOp::translate([1.0, 0.0, 0.0])], None),
+            (
+                "b",
+                vec![XformOp::scale([2.0, 2.0, 2.0])],
+                Some(BoundingBox {
+                    min: [-1.0; 3],
+                    max: [1.0; 3],
+                }),
+            ),
+            (
+                "c",
+                vec![XformOp::scale([3.0, 3.0, 3.0])],
+                Some(BoundingBox::empty()),
+            ),
+        ] {
+            let sample = XformSample {
+                ops,
+                inherits: true,
+                child_bounds,
+            };
+            let mut xform = xform_object(name, sample, &mut archive)?;
+            xform.add_child(mesh_object(&mut archive)?)?;
+            root.add_child(xform)?;
+        }
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+
+        let bounds = |full_name: &str| archive.load_bounds(full_name, 0.0, &data);
+        assert_bounds_eq(bounds("/a")?, [1.0, 0.0, 0.0], [2.0, 1.0, 0.0]);
+        assert_bounds_eq(bounds("/a/mesh")?, [1.0, 0.0, 0.0], [2.0, 1.0, 0.0]);
+        assert_bounds_eq(bounds("/b")?, [-2.0; 3], [2.0; 3]);
+        assert_bounds_eq(bounds("/b/mesh")?, [0.0; 3], [2.0, 2.0, 0.0]);
+        assert_bounds_eq(bounds("/c")?, [0.0; 3], [3.0, 3.0, 0.0]);
+        assert_bounds_eq(bounds("/")?, [-2.0; 3], [3.0, 3.0, 2.0]);
+        assert!(bounds("/a/missing").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn interpolated_matrices() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        let mut xform = XformWriter::new("xform", 0);
+        for (translation, angle) in [(0.0, 0.0), (2.0, 90.0)] {
+            let ops = vec![
+                XformOp::translate([translation, 0.0, 0.0]),
+                XformOp::rotate_y(angle),
+            ];
+            xform.add_sample(&XformSample::from_ops(ops), &mut archive)?;
+        }
+        let mut root = ObjectWriter::new_root();
+        root.add_child(xform.into_object_writer(&mut archive)?)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let object = archive.load_object("/xform", &data)?;
+        let xform = XformSchema::new_from_object_reader(&object, &data, &archive)?;
+
+        // the samples are at time 0 and 1
+        for (time, index) in [(0.0, 0), (1.0, 1), (-1.0, 0), (5.0, 1)] {
+            assert_matrix_eq(
+                xform.load_interpolated_matrix(time, &data)?,
+                xform.load_matrix_sample(index, &data)?,
+            );
+        }
+        assert_matrix_eq(
+            xform.load_interpolated_matrix(0.5, &data)?,
+            concatenate_ops(&[XformOp::translate([1.0, 0.0, 0.0]), XformOp::rotate_y(45.0)]),
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn samples_at_the_same_time_are_not_blended() -> Result<()> {
+        let mut archive = ArchiveWriter::new(Cursor::new(vec![]))?;
+        // every sample is at time 1
+        let time_sampling = archive.add_time_sampling(TimeSampling::uniform(0.0, 1.0));
+        let mut xform = XformWriter::new("xform", time_sampling);
+        for translation in [1.0, 2.0] {
+            let ops = vec![XformOp::translate([translation, 0.0, 0.0])];
+            xform.add_sample(&XformSample::from_ops(ops), &mut archive)?;
+        }
+        let mut root = ObjectWriter::new_root();
+        root.add_child(xform.into_object_writer(&mut archive)?)?;
+
+        let data = archive.finish(root)?.into_inner();
+        let archive = Archive::new(&data)?;
+        let object = archive.load_object("/xform", &data)?;
+        let xform = XformSchema::new_from_object_reader(&object, &data, &archive)?;
+        assert_matrix_eq(
+            xform.load_interpolated_matrix(1.0, &data)?,
+            xform.load_matrix_sample(1, &data)?,
+        );
+
+        Ok(())
+    }
 }
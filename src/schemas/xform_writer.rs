@@ -0,0 +1,266 @@
+use super::{
+    interpretation_meta_data, schema_meta_data, schema_object_meta_data, XformOp, XFORM_SCHEMA,
+};
+use crate::archive_writer::ArchiveWriter;
+use crate::metadata::MetaData;
+use crate::object_writer::ObjectWriter;
+use crate::pod::*;
+use crate::property::*;
+use crate::result::*;
+use std::collections::BTreeSet;
+use std::io::{Seek, Write};
+
+#[derive(Debug, Clone)]
+pub struct XformSample {
+    pub ops: Vec<XformOp>,
+    // When false the transform is not concatenated with the transforms of its parents.
+    pub inherits: bool,
+    pub child_bounds: Option<BoundingBox>,
+}
+
+impl Default for XformSample {
+    fn default() -> Self {
+        Self {
+            ops: vec![],
+            inherits: true,
+            child_bounds: None,
+        }
+    }
+}
+
+impl XformSample {
+    pub fn from_ops(ops: Vec<XformOp>) -> Self {
+        Self {
+            ops,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_matrix(matrix: [[f64; 4]; 4]) -> Self {
+        Self::from_ops(vec![XformOp::matrix(matrix)])
+    }
+}
+
+#[derive(Debug)]
+pub struct XformWriter {
+    name: String,
+    time_sampling_index: u32,
+
+    inherits: ScalarPropertyWriter,
+    ops: Option<ScalarPropertyWriter>,
+    vals: Option<PropertyWriter>,
+    child_bounds: Option<ScalarPropertyWriter>,
+    is_not_constant_identity: Option<ScalarPropertyWriter>,
+
+    last_child_bounds: Option<BoundingBox>,
+    op_codes: Vec<u8>,
+    first_channels: Vec<f64>,
+    animated_channels: BTreeSet<u32>,
+}
+
+impl XformWriter {
+    pub fn new(name: &str, time_sampling_index: u32) -> Self {
+        Self {
+            name: name.to_owned(),
+            time_sampling_index,
+
+            inherits: ScalarPropertyWriter::new(
+                ".inherits",
+                BOOL_TYPE,
+                MetaData::default(),
+                time_sampling_index,
+            ),
+            ops: None,
+            vals: None,
+            child_bounds: None,
+            is_not_constant_identity: None,
+
+            last_child_bounds: None,
+            op_codes: vec![],
+            first_channels: vec![],
+            animated_channels: BTreeSet::new(),
+        }
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.inherits.sample_count()
+    }
+
+    // The stack of operations has to be the same for every sample, only their channels can be
+    // animated.
+    pub fn add_sample<W: Write + Seek>(
+        &mut self,
+        sample: &XformSample,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<()> {
+        if sample
+            .ops
+            .iter()
+            .any(|op| op.channels.len() != op.operation_type.channel_count())
+        {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        let op_codes = sample.ops.iter().map(XformOp::encode).collect::<Vec<_>>();
+        let channels = sample
+            .ops
+            .iter()
+            .flat_map(|op| op.channels.iter().copied())
+            .collect::<Vec<_>>();
+
+        let sample_index = self.sample_count();
+        if sample_index == 0 {
+            self.create_op_properties(&op_codes, channels.len(), archive)?;
+            self.op_codes = op_codes;
+            self.first_channels = channels.clone();
+        } else if op_codes != self.op_codes {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        for (index, (value, first_value)) in channels.iter().zip(&self.first_channels).enumerate() {
+            if value.to_bits() != first_value.to_bits() {
+                self.animated_channels.insert(index as u32);
+            }
+        }
+
+        if self.is_not_constant_identity.is_none() && !sample.ops.iter().all(XformOp::is_identity) {
+            let mut is_not_constant_identity = ScalarPropertyWriter::new(
+                "isNotConstantIdentity",
+                BOOL_TYPE,
+                MetaData::default(),
+                self.time_sampling_index,
+            );
+            is_not_constant_identity.add_sample(&PodArray::Boolean(vec![true]), archive)?;
+            self.is_not_constant_identity = Some(is_not_constant_identity);
+        }
+
+        self.inherits
+            .add_sample(&PodArray::Boolean(vec![sample.inherits]), archive)?;
+
+        match &mut self.vals {
+            Some(PropertyWriter::Scalar(vals)) => {
+                vals.add_sample(&PodArray::F64(channels), archive)?
+            }
+            Some(PropertyWriter::Array(vals)) => {
+                vals.add_sample(&PodArray::F64(channels), archive)?
+            }
+            _ => {}
+        }
+
+        // the child bounds are back-filled with the first provided value, later samples without
+        // a value repeat the previous one
+        if let Some(bounds) = &sample.child_bounds {
+            self.last_child_bounds = Some(bounds.clone());
+        }
+        if let Some(bounds) = &self.last_child_bounds {
+            let time_sampling_index = self.time_sampling_index;
+            let child_bounds = self.child_bounds.get_or_insert_with(|| {
+                ScalarPropertyWriter::new(
+                    ".childBnds",
+                    BOX_TYPE,
+                    interpretation_meta_data("box"),
+                    time_sampling_index,
+                )
+            });
+            let bounds = bounds.to_pod_array();
+            while child_bounds.sample_count() <= sample_index {
+                child_bounds.add_sample(&bounds, archive)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn create_op_properties<W: Write + Seek>(
+        &mut self,
+        op_codes: &[u8],
+        channel_count: usize,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<()> {
+        if op_codes.is_empty() {
+            return Ok(());
+        }
+        if op_codes.len() > u8::MAX as usize {
+            return Err(UserError::InvalidParameter.into());
+        }
+
+        // the ops never change, so they are only written once
+        let mut ops = ScalarPropertyWriter::new(
+            ".ops",
+            DataType {
+                pod_type: PodType::U8,
+                extent: op_codes.len() as u8,
+            },
+            MetaData::default(),
+            self.time_sampling_index,
+        );
+        ops.add_sample(&PodArray::U8(op_codes.to_vec()), archive)?;
+        self.ops = Some(ops);
+
+        // the channels only fit in a scalar property when the count fits in its extent
+        self.vals = Some(if channel_count <= u8::MAX as usize {
+            ScalarPropertyWriter::new(
+                ".vals",
+                DataType {
+                    pod_type: PodType::F64,
+                    extent: channel_count as u8,
+                },
+                MetaData::default(),
+                self.time_sampling_index,
+            )
+            .into()
+        } else {
+            ArrayPropertyWriter::new(
+                ".vals",
+                F64_TYPE,
+                MetaData::default(),
+                self.time_sampling_index,
+            )
+            .into()
+        });
+
+        Ok(())
+    }
+
+    // The animated channels are only known once every sample has been added, so they are
+    // written here.
+    pub fn into_object_writer<W: Write + Seek>(
+        self,
+        archive: &mut ArchiveWriter<W>,
+    ) -> Result<ObjectWriter> {
+        let mut xform = CompoundPropertyWriter::new(".xform", schema_meta_data(XFORM_SCHEMA, ""));
+        if let Some(child_bounds) = self.child_bounds {
+            xform.add_sub_property(child_bounds)?;
+        }
+        xform.add_sub_property(self.inherits)?;
+        if let Some(ops) = self.ops {
+            xform.add_sub_property(ops)?;
+        }
+        if let Some(vals) = self.vals {
+            xform.add_sub_property(vals)?;
+        }
+        if !self.animated_channels.is_empty() {
+            let mut anim_channels = ArrayPropertyWriter::new(
+                ".animChans",
+                U32_TYPE,
+                MetaData::default(),
+                self.time_sampling_index,
+            );
+            anim_channels.add_sample(
+                &PodArray::U32(self.animated_channels.into_iter().collect()),
+                archive,
+            )?;
+            xform.add_sub_property(anim_channels)?;
+        }
+        if let Some(is_not_constant_identity) = self.is_not_constant_identity {
+            xform.add_sub_property(is_not_constant_identity)?;
+        }
+
+        let mut object = ObjectWriter::new(
+            &self.name,
+            schema_object_meta_data(XFORM_SCHEMA, "", ".xform"),
+        );
+        object.properties_mut().add_sub_property(xform)?;
+        Ok(object)
+    }
+}
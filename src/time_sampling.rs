@@ -1,5 +1,5 @@
-use byteorder::{LittleEndian, ReadBytesExt};
-use std::rc::Rc;
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use std::sync::Arc;
 
 use crate::chunks::DataChunk;
 use crate::reader::ArchiveReader;
@@ -7,21 +7,172 @@ use crate::result::*;
 
 const ACYCLIC_NUM_SAMPLES: u32 = u32::MAX;
 const ACYCLIC_TIME_PER_CYCLE: f64 = f64::MAX / 32.0;
-#[derive(Debug)]
+// Tolerance used when comparing times against sample times.
+const TIME_EPSILON: f64 = f64::EPSILON * 32.0;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct TimeSamplingType {
     pub num_samples_per_cycle: u32,
     pub time_per_cycle: f64,
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TimeSampling {
     pub sampling_type: TimeSamplingType,
     pub samples: Vec<f64>,
 }
 
+impl TimeSampling {
+    pub fn uniform(time_per_cycle: f64, start_time: f64) -> Self {
+        Self {
+            sampling_type: TimeSamplingType {
+                num_samples_per_cycle: 1,
+                time_per_cycle,
+            },
+            samples: vec![start_time],
+        }
+    }
+
+    pub fn cyclic(time_per_cycle: f64, samples: Vec<f64>) -> Self {
+        Self {
+            sampling_type: TimeSamplingType {
+                num_samples_per_cycle: samples.len() as u32,
+                time_per_cycle,
+            },
+            samples,
+        }
+    }
+
+    pub fn acyclic(samples: Vec<f64>) -> Self {
+        Self {
+            sampling_type: TimeSamplingType {
+                num_samples_per_cycle: ACYCLIC_NUM_SAMPLES,
+                time_per_cycle: ACYCLIC_TIME_PER_CYCLE,
+            },
+            samples,
+        }
+    }
+
+    fn is_acyclic(&self) -> bool {
+        self.sampling_type.num_samples_per_cycle == ACYCLIC_NUM_SAMPLES
+    }
+
+    pub fn sample_time(&self, index: u32) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+
+        if self.is_acyclic() {
+            let index = (index as usize).min(self.samples.len() - 1);
+            return self.samples[index];
+        }
+
+        // uniform and cyclic samplings repeat their samples every cycle
+        let samples_per_cycle = self.samples.len() as u32;
+        let cycle = index / samples_per_cycle;
+        self.samples[(index % samples_per_cycle) as usize]
+            + self.sampling_type.time_per_cycle * cycle as f64
+    }
+
+    // Returns the index of the last sample at or before `time`, or the first sample when `time`
+    // lies before all of them.
+    pub fn floor_index(&self, time: f64, sample_count: u32) -> u32 {
+        let time = time + TIME_EPSILON;
+
+        // sample times always increase, so the index can be searched for
+        let mut low = 0;
+        let mut high = sample_count;
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if self.sample_time(middle) <= time {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        low.saturating_sub(1)
+    }
+
+    // Returns the index of the first sample at or after `time`, or the last sample when `time`
+    // lies after all of them.
+    pub fn ceil_index(&self, time: f64, sample_count: u32) -> u32 {
+        let time = time - TIME_EPSILON;
+
+        let mut low = 0;
+        let mut high = sample_count;
+        while low < high {
+            let middle = low + (high - low) / 2;
+            if self.sample_time(middle) < time {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        low.min(sample_count.saturating_sub(1))
+    }
+
+    // Returns the index of the sample closest to `time`, preferring the earlier sample when
+    // both are equally close.
+    pub fn near_index(&self, time: f64, sample_count: u32) -> u32 {
+        let floor_index = self.floor_index(time, sample_count);
+        let ceil_index = self.ceil_index(time, sample_count);
+
+        let floor_distance = (time - self.sample_time(floor_index)).abs();
+        let ceil_distance = (self.sample_time(ceil_index) - time).abs();
+        if floor_distance <= ceil_distance {
+            floor_index
+        } else {
+            ceil_index
+        }
+    }
+
+    // Returns the times of the first and the last sample.
+    pub fn time_range(&self, sample_count: u32) -> (f64, f64) {
+        (
+            self.sample_time(0),
+            self.sample_time(sample_count.saturating_sub(1)),
+        )
+    }
+}
+
+// Selects a sample either by its index or by a time. Times select the sample at or before them
+// (`Floor`), at or after them (`Ceil`) or the one closest to them (`Near`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleSelector {
+    Index(u32),
+    Floor(f64),
+    Ceil(f64),
+    Near(f64),
+}
+
+impl SampleSelector {
+    pub fn sample_index(self, time_sampling: &TimeSampling, sample_count: u32) -> u32 {
+        match self {
+            SampleSelector::Index(index) => index,
+            SampleSelector::Floor(time) => time_sampling.floor_index(time, sample_count),
+            SampleSelector::Ceil(time) => time_sampling.ceil_index(time, sample_count),
+            SampleSelector::Near(time) => time_sampling.near_index(time, sample_count),
+        }
+    }
+}
+
+impl From<u32> for SampleSelector {
+    fn from(index: u32) -> Self {
+        SampleSelector::Index(index)
+    }
+}
+
+impl Default for TimeSampling {
+    fn default() -> Self {
+        Self::uniform(1.0, 0.0)
+    }
+}
+
 pub(crate) fn read_time_samplings_and_max(
     data: &DataChunk,
-    reader: &mut dyn ArchiveReader,
-) -> Result<(Vec<Rc<TimeSampling>>, Vec<i64>)> {
+    reader: &dyn ArchiveReader,
+) -> Result<(Vec<Arc<TimeSampling>>, Vec<i64>)> {
     let mut buffer = vec![0u8; data.size as usize];
     data.read(0, reader, &mut buffer)?;
     let mut buffer = std::io::Cursor::new(buffer);
@@ -56,7 +207,7 @@ pub(crate) fn read_time_samplings_and_max(
             }
         };
 
-        out_time_samples.push(Rc::new(TimeSampling {
+        out_time_samples.push(Arc::new(TimeSampling {
             sampling_type,
             samples,
         }));
@@ -64,3 +215,118 @@ pub(crate) fn read_time_samplings_and_max(
 
     Ok((out_time_samples, out_max_samples))
 }
+
+pub(crate) fn write_time_samplings_and_max(
+    time_samplings: &[TimeSampling],
+    max_samples: &[u32],
+) -> Result<Vec<u8>> {
+    let mut buffer = vec![];
+
+    for (time_sampling, &max_sample) in time_samplings.iter().zip(max_samples) {
+        buffer.write_u32::<LittleEndian>(max_sample)?;
+        buffer.write_f64::<LittleEndian>(time_sampling.sampling_type.time_per_cycle)?;
+        // acyclic samplings store their sample count instead of `ACYCLIC_NUM_SAMPLES`
+        buffer.write_u32::<LittleEndian>(time_sampling.samples.len() as u32)?;
+        for &sample in &time_sampling.samples {
+            buffer.write_f64::<LittleEndian>(sample)?;
+        }
+    }
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_times() {
+        let uniform = TimeSampling::uniform(0.5, 1.0);
+        assert_eq!(uniform.sample_time(0), 1.0);
+        assert_eq!(uniform.sample_time(3), 2.5);
+
+        let cyclic = TimeSampling::cyclic(1.0, vec![0.0, 0.25]);
+        let times: Vec<_> = (0..5).map(|index| cyclic.sample_time(index)).collect();
+        assert_eq!(times, [0.0, 0.25, 1.0, 1.25, 2.0]);
+
+        // acyclic samplings hold the last sample
+        let acyclic = TimeSampling::acyclic(vec![0.0, 0.25, 2.0]);
+        assert_eq!(acyclic.sample_time(1), 0.25);
+        assert_eq!(acyclic.sample_time(2), 2.0);
+        assert_eq!(acyclic.sample_time(5), 2.0);
+
+        assert_eq!(TimeSampling::acyclic(vec![]).sample_time(1), 0.0);
+    }
+
+    #[test]
+    fn sample_indices() {
+        let samplings = [
+            // sample times 1, 1.5, 2, 2.5
+            TimeSampling::uniform(0.5, 1.0),
+            // sample times 1, 1.25, 2, 2.25
+            TimeSampling::cyclic(1.0, vec![1.0, 1.25]),
+            // sample times 1, 1.5, 1.75, 3
+            TimeSampling::acyclic(vec![1.0, 1.5, 1.75, 3.0]),
+        ];
+        let sample_count = 4;
+
+        for time_sampling in &samplings {
+            let indices = |time: f64| {
+                (
+                    time_sampling.floor_index(time, sample_count),
+                    time_sampling.ceil_index(time, sample_count),
+                    time_sampling.near_index(time, sample_count),
+                )
+            };
+
+            for index in 0..sample_count {
+                let time = time_sampling.sample_time(index);
+                assert_eq!(indices(time), (index, index, index));
+                // times that only differ by rounding errors still select the sample
+                assert_eq!(indices(time - TIME_EPSILON / 2.0), (index, index, index));
+                assert_eq!(indices(time + TIME_EPSILON / 2.0), (index, index, index));
+            }
+
+            // before the first and after the last sample
+            assert_eq!(indices(0.0), (0, 0, 0));
+            assert_eq!(indices(10.0), (3, 3, 3));
+
+            // between two samples, a little closer to the second one
+            let first = time_sampling.sample_time(1);
+            let second = time_sampling.sample_time(2);
+            assert_eq!(indices(first + (second - first) * 0.75), (1, 2, 2));
+            assert_eq!(indices(first + (second - first) * 0.25), (1, 2, 1));
+        }
+    }
+
+    #[test]
+    fn near_index_prefers_the_earlier_sample() {
+        let time_sampling = TimeSampling::uniform(1.0, 0.0);
+        assert_eq!(time_sampling.near_index(0.5, 2), 0);
+        assert_eq!(time_sampling.near_index(1.5, 3), 1);
+        assert_eq!(SampleSelector::Near(0.5).sample_index(&time_sampling, 2), 0);
+    }
+
+    #[test]
+    fn empty_properties() {
+        let time_sampling = TimeSampling::uniform(0.5, 1.0);
+        assert_eq!(time_sampling.floor_index(2.0, 0), 0);
+        assert_eq!(time_sampling.ceil_index(2.0, 0), 0);
+        assert_eq!(time_sampling.near_index(2.0, 0), 0);
+        assert_eq!(time_sampling.time_range(0), (1.0, 1.0));
+    }
+
+    #[test]
+    fn time_ranges() {
+        assert_eq!(TimeSampling::uniform(0.5, 1.0).time_range(4), (1.0, 2.5));
+        assert_eq!(
+            TimeSampling::cyclic(1.0, vec![0.0, 0.25]).time_range(3),
+            (0.0, 1.0)
+        );
+        assert_eq!(
+            TimeSampling::acyclic(vec![0.0, 0.25, 2.0]).time_range(3),
+            (0.0, 2.0)
+        );
+        assert_eq!(TimeSampling::uniform(0.5, 1.0).time_range(1), (1.0, 1.0));
+    }
+}